@@ -0,0 +1,158 @@
+use macroquad::file::load_string;
+
+use crate::{enemy::EnemySize, simulation::Simulation};
+
+/// Value of the run an achievement waits for.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum AchievementStat {
+    Score,
+    Kills,
+    BigKills,
+    Wave,
+    NoHitWaves,
+    Combo,
+    /// Seconds survived.
+    Time,
+}
+
+impl AchievementStat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "score" => Some(AchievementStat::Score),
+            "kills" => Some(AchievementStat::Kills),
+            "big_kills" => Some(AchievementStat::BigKills),
+            "wave" => Some(AchievementStat::Wave),
+            "no_hit_waves" => Some(AchievementStat::NoHitWaves),
+            "combo" => Some(AchievementStat::Combo),
+            "time" => Some(AchievementStat::Time),
+            _ => None,
+        }
+    }
+
+    pub fn value(&self, simulation: &Simulation) -> u32 {
+        let stats = &simulation.stats;
+
+        match self {
+            AchievementStat::Score => simulation.score,
+            AchievementStat::Kills => stats.enemies_destroyed(),
+            AchievementStat::BigKills => stats.enemies_destroyed_of(EnemySize::Big),
+            AchievementStat::Wave => simulation.enemies.wave,
+            AchievementStat::NoHitWaves => stats.no_hit_waves,
+            AchievementStat::Combo => stats.max_combo,
+            AchievementStat::Time => stats.time_survived as u32,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Achievement {
+    /// Name kept in the save file.
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub stat: AchievementStat,
+    pub target: u32,
+}
+
+impl Achievement {
+    /// Loads the definitions from the assets folder.
+    pub async fn load_all() -> Result<Vec<Achievement>, String> {
+        let text = load_string("achievements.txt").await.map_err(|error| error.to_string())?;
+        Self::parse_all(&text)
+    }
+
+    /// Reads the definitions of `assets/achievements.txt`, a `[id]` line
+    /// followed by the fields of each achievement.
+    pub fn parse_all(text: &str) -> Result<Vec<Achievement>, String> {
+        let mut achievements: Vec<Achievement> = Vec::new();
+
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            let error = |message: &str| format!("line {}: {}", index + 1, message);
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(id) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+                if achievements.iter().any(|achievement| achievement.id == id) {
+                    return Err(error("duplicate id"));
+                }
+
+                achievements.push(Achievement {
+                    id: id.to_string(),
+                    name: id.to_string(),
+                    description: String::new(),
+                    stat: AchievementStat::Score,
+                    target: 0,
+                });
+                continue;
+            }
+
+            let achievement = achievements.last_mut().ok_or_else(|| error("field before the first [id]"))?;
+            let (field, value) = line.split_once(' ').ok_or_else(|| error("missing value"))?;
+
+            match field {
+                "name" => achievement.name = value.to_string(),
+                "description" => achievement.description = value.to_string(),
+                "unlock" => {
+                    let (stat, target) = value.split_once(' ').ok_or_else(|| error("expected a stat and a target"))?;
+                    achievement.stat = AchievementStat::from_name(stat).ok_or_else(|| error("unknown stat"))?;
+                    achievement.target = target.parse().map_err(|_| error("invalid target"))?;
+                }
+                _ => return Err(error("unknown field")),
+            }
+        }
+
+        match achievements.iter().find(|achievement| achievement.target == 0) {
+            Some(achievement) => Err(format!("{} has no unlock condition", achievement.id)),
+            None => Ok(achievements),
+        }
+    }
+}
+
+/// Achievement definitions and which of them the player unlocked.
+#[derive(Default)]
+pub struct Achievements {
+    definitions: Vec<Achievement>,
+    /// Ids in unlock order, including ids no longer defined so they come
+    /// back if their definition does.
+    unlocked: Vec<String>,
+}
+
+impl Achievements {
+    pub fn new(definitions: Vec<Achievement>, unlocked: Vec<String>) -> Self {
+        Achievements { definitions, unlocked }
+    }
+
+    pub fn all(&self) -> &[Achievement] {
+        &self.definitions
+    }
+
+    pub fn unlocked(&self) -> &[String] {
+        &self.unlocked
+    }
+
+    pub fn is_unlocked(&self, id: &str) -> bool {
+        self.unlocked.iter().any(|unlocked| unlocked == id)
+    }
+
+    /// Defined achievements that are unlocked.
+    pub fn unlocked_count(&self) -> usize {
+        self.definitions.iter().filter(|achievement| self.is_unlocked(&achievement.id)).count()
+    }
+
+    /// Unlocks the achievements the run reached, returns their names.
+    pub fn check(&mut self, simulation: &Simulation) -> Vec<String> {
+        let mut names = Vec::new();
+
+        for achievement in self.definitions.iter() {
+            if achievement.stat.value(simulation) >= achievement.target && !self.unlocked.contains(&achievement.id) {
+                self.unlocked.push(achievement.id.clone());
+                names.push(achievement.name.clone());
+            }
+        }
+
+        names
+    }
+}
@@ -0,0 +1,378 @@
+//! Local server of the online leaderboard, see `docs/online-leaderboard.md`.
+//!
+//! Usage: `leaderboard_server [address] [data file]`
+
+use std::{
+    env,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    net::{TcpListener, TcpStream},
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use macroquad_tst::{
+    constants::ASSETS_PATH,
+    http::{self, Request, Response},
+    json::Json,
+    mutators::Mutators,
+    replay::Replay,
+    sprites_config::SpriteMasks,
+    verification::{self, RunSummary},
+};
+
+const DEFAULT_ADDRESS: &str = "127.0.0.1:7878";
+const DEFAULT_DATA_FILE: &str = "leaderboard_server.jsonl";
+const DEFAULT_LIMIT: usize = 10;
+const MAX_LIMIT: usize = 100;
+const MAX_NAME_LENGTH: usize = 16;
+
+/// A run accepted by the server.
+struct Score {
+    mode: String,
+    difficulty: String,
+    name: String,
+    score: u32,
+    wave: u32,
+    duration: f64,
+    timestamp: u64,
+    seed: String,
+    /// Mutators as written in replays.
+    mutators: String,
+    game_version: String,
+    replay: String,
+}
+
+impl Score {
+    fn from_json(json: &Json) -> Result<Self, String> {
+        let text = |key: &str| {
+            json.get(key)
+                .and_then(Json::as_str)
+                .map(str::to_string)
+                .ok_or_else(|| format!("missing {}", key))
+        };
+        let number = |key: &str| json.get(key).and_then(Json::as_u32).ok_or_else(|| format!("missing {}", key));
+
+        let score = Score {
+            mode: text("mode")?,
+            difficulty: text("difficulty")?,
+            name: text("name")?,
+            score: number("score")?,
+            wave: number("wave")?,
+            duration: json.get("duration").and_then(Json::as_f64).ok_or("missing duration")?,
+            timestamp: json.get("timestamp").and_then(Json::as_f64).map_or_else(now, |timestamp| timestamp as u64),
+            seed: text("seed")?,
+            // Submissions from before mutators have none
+            mutators: text("mutators").unwrap_or_else(|_| Mutators::default().to_text()),
+            game_version: text("game_version")?,
+            replay: text("replay")?,
+        };
+
+        if score.name.is_empty() || score.name.chars().count() > MAX_NAME_LENGTH {
+            return Err("invalid name".to_string());
+        }
+        if score.seed.parse::<u64>().is_err() {
+            return Err("invalid seed".to_string());
+        }
+        if Mutators::from_text(&score.mutators).is_none() {
+            return Err("invalid mutators".to_string());
+        }
+
+        Ok(score)
+    }
+
+    /// Plays the replay again to check it gives the submitted results.
+    fn verify(&self, masks: &SpriteMasks) -> Result<(), String> {
+        let replay = Replay::from_text(&self.replay).map_err(|error| format!("invalid replay: {}", error))?;
+        if replay.seed.to_string() != self.seed {
+            return Err("the replay has another seed".to_string());
+        }
+        if replay.rules.practice.is_some() {
+            return Err("practice runs are not ranked".to_string());
+        }
+        if replay.rules.mode.name() != self.mode {
+            return Err(format!("the replay was played in {}", replay.rules.mode.name()));
+        }
+        if replay.rules.difficulty.name() != self.difficulty {
+            return Err(format!("the replay was played on {}", replay.rules.difficulty.name()));
+        }
+        if replay.rules.mutators.to_text() != self.mutators {
+            return Err(format!("the replay was played with the mutators {}", replay.rules.mutators.to_text()));
+        }
+
+        let claim = RunSummary {
+            score: self.score,
+            wave: self.wave,
+            duration: self.duration as f32,
+        };
+        verification::verify(&replay, claim, masks).map(|_| ()).map_err(|rejection| rejection.to_string())
+    }
+
+    /// Stored form, the submission plus the time it was accepted.
+    fn to_json(&self) -> Json {
+        Json::Object(vec![
+            ("game_version".to_string(), self.game_version.as_str().into()),
+            ("mode".to_string(), self.mode.as_str().into()),
+            ("difficulty".to_string(), self.difficulty.as_str().into()),
+            ("name".to_string(), self.name.as_str().into()),
+            ("score".to_string(), self.score.into()),
+            ("wave".to_string(), self.wave.into()),
+            ("duration".to_string(), Json::Number(self.duration)),
+            ("timestamp".to_string(), Json::Number(self.timestamp as f64)),
+            ("seed".to_string(), self.seed.as_str().into()),
+            ("mutators".to_string(), self.mutators.as_str().into()),
+            ("replay".to_string(), self.replay.as_str().into()),
+        ])
+    }
+
+    fn to_ranked_json(&self, rank: usize) -> Json {
+        Json::Object(vec![
+            ("rank".to_string(), (rank as u32).into()),
+            ("name".to_string(), self.name.as_str().into()),
+            ("score".to_string(), self.score.into()),
+            ("wave".to_string(), self.wave.into()),
+            ("duration".to_string(), Json::Number(self.duration)),
+            ("timestamp".to_string(), Json::Number(self.timestamp as f64)),
+            ("seed".to_string(), self.seed.as_str().into()),
+            ("mutators".to_string(), self.mutators.as_str().into()),
+        ])
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_secs())
+}
+
+fn json_response(status: u16, body: Json) -> Response {
+    Response { status, body: body.to_string() }
+}
+
+fn error_response(status: u16, message: &str) -> Response {
+    json_response(status, Json::Object(vec![("error".to_string(), message.into())]))
+}
+
+struct Server {
+    /// Sorted by score, highest first, ties keeping the oldest first.
+    scores: Vec<Score>,
+    data_file: String,
+    masks: SpriteMasks,
+}
+
+impl Server {
+    /// Stored scores were verified when they were submitted.
+    fn load(data_file: String, masks: SpriteMasks) -> Self {
+        let mut server = Server { scores: Vec::new(), data_file, masks };
+
+        if let Ok(text) = fs::read_to_string(&server.data_file) {
+            for line in text.lines() {
+                match Json::parse(line).and_then(|json| Score::from_json(&json)) {
+                    Ok(score) => {
+                        server.insert(score);
+                    }
+                    Err(error) => eprintln!("Skipping a stored score: {}", error),
+                }
+            }
+        }
+
+        server
+    }
+
+    /// Returns the position of the score in its category.
+    fn insert(&mut self, score: Score) -> usize {
+        let index = self.scores.iter().position(|other| score.score > other.score).unwrap_or(self.scores.len());
+        self.scores.insert(index, score);
+
+        let score = &self.scores[index];
+        self.category(&score.mode, &score.difficulty).take_while(|other| !std::ptr::eq(*other, score)).count()
+    }
+
+    fn category<'a>(&'a self, mode: &'a str, difficulty: &'a str) -> impl Iterator<Item = &'a Score> {
+        self.scores.iter().filter(move |score| score.mode == mode && score.difficulty == difficulty)
+    }
+
+    fn append_to_data_file(&self, score: &Score) -> io::Result<()> {
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.data_file)?;
+        writeln!(file, "{}", score.to_json())
+    }
+
+    fn submit(&mut self, request: &Request) -> Response {
+        let score = match Json::parse(&request.body).and_then(|json| Score::from_json(&json)) {
+            Ok(score) => score,
+            Err(error) => return error_response(400, &error),
+        };
+        if let Err(error) = score.verify(&self.masks) {
+            return error_response(400, &error);
+        }
+
+        let duplicate = self.scores.iter().any(|other| {
+            other.seed == score.seed && other.name == score.name && other.score == score.score
+        });
+        if duplicate {
+            return error_response(409, "this run was already submitted");
+        }
+
+        if let Err(error) = self.append_to_data_file(&score) {
+            eprintln!("Could not store the score: {}", error);
+        }
+
+        let rank = self.insert(score) + 1;
+        json_response(201, Json::Object(vec![("rank".to_string(), (rank as u32).into())]))
+    }
+
+    fn leaderboard(&self, request: &Request) -> Response {
+        let (Some(mode), Some(difficulty)) = (request.query("mode"), request.query("difficulty")) else {
+            return error_response(400, "mode and difficulty are required");
+        };
+        let limit = request
+            .query("limit")
+            .and_then(|limit| limit.parse().ok())
+            .unwrap_or(DEFAULT_LIMIT)
+            .min(MAX_LIMIT);
+
+        let scores = self
+            .category(&mode, &difficulty)
+            .take(limit)
+            .enumerate()
+            .map(|(index, score)| score.to_ranked_json(index + 1))
+            .collect();
+
+        json_response(200, Json::Object(vec![("scores".to_string(), Json::Array(scores))]))
+    }
+
+    fn handle(&mut self, stream: &mut TcpStream) -> io::Result<()> {
+        let request = http::read_request(stream)?;
+
+        let response = match (request.method.as_str(), request.route()) {
+            ("POST", "/scores") => self.submit(&request),
+            ("GET", "/scores") => self.leaderboard(&request),
+            _ => error_response(404, "not found"),
+        };
+        println!("{} {} -> {}", request.method, request.route(), response.status);
+
+        http::write_response(stream, &response)
+    }
+}
+
+fn main() -> io::Result<()> {
+    let mut arguments = env::args().skip(1);
+    let address = arguments.next().unwrap_or_else(|| DEFAULT_ADDRESS.to_string());
+    let data_file = arguments.next().unwrap_or_else(|| DEFAULT_DATA_FILE.to_string());
+
+    // The sprites are needed to play the submitted replays again
+    let masks = SpriteMasks::load(Path::new(ASSETS_PATH))?;
+    let mut server = Server::load(data_file, masks);
+    let listener = TcpListener::bind(&address)?;
+    println!("Leaderboard server listening on http://{} with {} scores", address, server.scores.len());
+
+    // One request at a time is plenty for local testing
+    for stream in listener.incoming() {
+        let mut stream = match stream {
+            Ok(stream) => stream,
+            Err(error) => {
+                eprintln!("Connection failed: {}", error);
+                continue;
+            }
+        };
+
+        if let Err(error) = server.handle(&mut stream) {
+            eprintln!("Request failed: {}", error);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(mode: &str, difficulty: &str, name: &str, score: u32) -> Score {
+        Score {
+            mode: mode.to_string(),
+            difficulty: difficulty.to_string(),
+            name: name.to_string(),
+            score,
+            wave: 3,
+            duration: 95.5,
+            timestamp: 1_760_000_000,
+            seed: "1760000000000".to_string(),
+            mutators: "none".to_string(),
+            game_version: "0.1.0".to_string(),
+            replay: "macroquad-tst replay\n".to_string(),
+        }
+    }
+
+    fn server() -> Server {
+        let masks = SpriteMasks::load(Path::new(ASSETS_PATH)).unwrap();
+        Server { scores: Vec::new(), data_file: String::new(), masks }
+    }
+
+    fn names<'a>(server: &'a Server, mode: &'a str, difficulty: &'a str) -> Vec<&'a str> {
+        server.category(mode, difficulty).map(|score| score.name.as_str()).collect()
+    }
+
+    #[test]
+    fn insert_ranks_within_the_category() {
+        let mut server = server();
+
+        assert_eq!(server.insert(score("Endless", "Normal", "AAA", 100)), 0);
+        assert_eq!(server.insert(score("Endless", "Hard", "BBB", 500)), 0);
+        assert_eq!(server.insert(score("Campaign", "Normal", "CCC", 300)), 0);
+        assert_eq!(server.insert(score("Endless", "Normal", "DDD", 200)), 0);
+        assert_eq!(server.insert(score("Endless", "Normal", "EEE", 50)), 2);
+
+        assert_eq!(names(&server, "Endless", "Normal"), ["DDD", "AAA", "EEE"]);
+        assert_eq!(names(&server, "Endless", "Hard"), ["BBB"]);
+    }
+
+    #[test]
+    fn ties_rank_after_older_scores() {
+        let mut server = server();
+        server.insert(score("Endless", "Normal", "AAA", 100));
+        server.insert(score("Endless", "Normal", "BBB", 100));
+        server.insert(score("Survival", "Normal", "CCC", 100));
+
+        assert_eq!(server.insert(score("Endless", "Normal", "DDD", 100)), 2);
+        assert_eq!(names(&server, "Endless", "Normal"), ["AAA", "BBB", "DDD"]);
+    }
+
+    #[test]
+    fn stored_scores_round_trip() {
+        let stored = score("Time Attack", "Insane", "ÉLA", 1234);
+        let text = stored.to_json().to_string();
+        let loaded = Score::from_json(&Json::parse(&text).unwrap()).unwrap();
+
+        assert_eq!(loaded.to_json(), stored.to_json());
+    }
+
+    #[test]
+    fn submissions_without_mutators_have_none() {
+        let mut json = score("Endless", "Normal", "AAA", 100).to_json();
+        if let Json::Object(members) = &mut json {
+            members.retain(|(name, _)| name != "mutators");
+        }
+
+        assert_eq!(Score::from_json(&json).unwrap().mutators, "none");
+    }
+
+    #[test]
+    fn rejects_invalid_submissions() {
+        let with = |key: &str, value: Json| {
+            let mut json = score("Endless", "Normal", "AAA", 100).to_json();
+            if let Json::Object(members) = &mut json {
+                members.retain(|(name, _)| name != key);
+                members.push((key.to_string(), value));
+            }
+            Score::from_json(&json).err()
+        };
+
+        assert_eq!(with("name", "".into()).as_deref(), Some("invalid name"));
+        assert_eq!(with("name", "ABCDEFGHIJKLMNOPQ".into()).as_deref(), Some("invalid name"));
+        assert_eq!(with("seed", "-1".into()).as_deref(), Some("invalid seed"));
+        assert_eq!(with("seed", Json::Number(1.0)).as_deref(), Some("missing seed"));
+        assert_eq!(with("mutators", "unknown".into()).as_deref(), Some("invalid mutators"));
+        assert_eq!(with("score", Json::Number(1.5)).as_deref(), Some("missing score"));
+        assert_eq!(with("replay", Json::Null).as_deref(), Some("missing replay"));
+        assert!(Score::from_json(&Json::Array(vec![])).is_err());
+    }
+}
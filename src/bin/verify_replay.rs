@@ -0,0 +1,52 @@
+//! Plays a replay again without a window and checks it backs a claimed
+//! result.
+//!
+//! Usage: `verify_replay <replay file> <score> <wave> <duration>`
+
+use std::{env, path::Path, process::ExitCode};
+
+use macroquad_tst::{constants::ASSETS_PATH, replay::Replay, sprites_config::SpriteMasks, verification::{self, RunSummary}};
+
+const USAGE: &str = "usage: verify_replay <replay file> <score> <wave> <duration>";
+
+fn parse_claim(arguments: &[String]) -> Option<RunSummary> {
+    Some(RunSummary {
+        score: arguments.first()?.parse().ok()?,
+        wave: arguments.get(1)?.parse().ok()?,
+        duration: arguments.get(2)?.parse().ok()?,
+    })
+}
+
+fn main() -> ExitCode {
+    let arguments: Vec<String> = env::args().skip(1).collect();
+    let (Some(path), Some(claim)) = (arguments.first(), parse_claim(&arguments[1.min(arguments.len())..])) else {
+        eprintln!("{}", USAGE);
+        return ExitCode::from(2);
+    };
+
+    let replay = match Replay::load(Path::new(path)) {
+        Ok(replay) => replay,
+        Err(error) => {
+            eprintln!("Could not read {}: {}", path, error);
+            return ExitCode::from(2);
+        }
+    };
+    let masks = match SpriteMasks::load(Path::new(ASSETS_PATH)) {
+        Ok(masks) => masks,
+        Err(error) => {
+            eprintln!("Could not load the sprites from {}: {}", ASSETS_PATH, error);
+            return ExitCode::from(2);
+        }
+    };
+
+    match verification::verify(&replay, claim, &masks) {
+        Ok(result) => {
+            println!("Verified: {} over {} frames", result, replay.frames.len());
+            ExitCode::SUCCESS
+        }
+        Err(rejection) => {
+            println!("Rejected: {}", rejection);
+            ExitCode::FAILURE
+        }
+    }
+}
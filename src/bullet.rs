@@ -1,76 +1,106 @@
-use macroquad::{color::{RED, WHITE}, math::{vec2}, prelude::animation::AnimatedSprite, texture::{draw_texture_ex, DrawTextureParams}};
+use std::f32::consts::{FRAC_1_SQRT_2, FRAC_PI_4};
 
-use crate::{sprites_config::SpritesConfig, collidable::Collidable, hero::Hero, shape::{Shape, ShapeType}};
+use macroquad::math::{vec2, Vec2};
 
-pub struct Bullet {
-    pub shape: Shape,
-    sprite: AnimatedSprite,
-}
-
-impl Collidable for Bullet {
-    fn shape_type(&self) -> ShapeType {
-        self.shape.shape_type()
-    }
+use crate::{sprites_config::SpritesConfig, collision_layers::CollisionLayer, components::{Collider, Health, Lifetime, Sprite, SpriteTexture, Team, Transform, Velocity}, shape::ShapeType, world::{Entity, EntityPool, World}};
 
-    fn position(&self) -> macroquad::prelude::Vec2 {
-        self.shape.position()
-    }
+const BULLET_SIZE: f32 = 32.0;
+const ENEMY_BULLET_SIZE: f32 = 20.0;
+const BULLET_LIFETIME: f32 = 5.0;
 
-    fn size(&self) -> f32 {
-        self.shape.size()
-    }
-}
+/// Spawns the laser bolts of the hero and of the enemies.
+pub struct Bullet;
 
 impl Bullet {
 
-    pub fn new(hero: &Hero) -> Self {
-        let mut shape = Shape {
-            shape_type: ShapeType::Square,
-            size: 32.0,
-            speed: hero.get_speed() * 2.,
-            position: hero.position(),
-            color: RED,
-            collided: false,
-        };
-        shape.position.y -= 24.; // Adjust bullet position to be above the hero
+    /// Spawns a bolt of the hero that goes through `hit_points` enemies,
+    /// returns `None` when the bullet pool is full.
+    pub fn spawn(world: &mut World, mut position: Vec2, speed: f32, hit_points: u32) -> Option<Entity> {
+        position.y -= 24.; // Adjust bullet position to be above the hero
 
-        Bullet {
-            shape,
-            sprite: SpritesConfig::get_bullet_sprite(),
-        }
-    }
+        let entity = world.spawn(EntityPool::Bullets)?
+            .with(Transform::new(position, BULLET_SIZE))
+            .with(Velocity {
+                linear: vec2(0.0, -speed),
+                angular: 0.0,
+            })
+            .with(Collider {
+                shape_type: ShapeType::Square,
+                layer: CollisionLayer::HeroProjectile,
+            })
+            .with(Health { hit_points })
+            .with(Team::Hero)
+            .with(Lifetime { remaining: BULLET_LIFETIME })
+            .with(Sprite {
+                texture: SpriteTexture::Bullet,
+                animation: SpritesConfig::get_bullet_sprite(),
+                draw_size: vec2(BULLET_SIZE, BULLET_SIZE),
+                elapsed: 0.0,
+            })
+            .build();
 
-    pub fn get_speed(&self) -> f32 {
-        self.shape.speed
+        Some(entity)
     }
 
-    pub fn get_collided(&self) -> bool {
-        self.shape.collided
-    }
+    /// Spawns a bolt fired down by an enemy at `position`, returns `None`
+    /// when the bullet pool is full.
+    pub fn spawn_enemy(world: &mut World, position: Vec2, speed: f32) -> Option<Entity> {
+        let mut transform = Transform::new(position, ENEMY_BULLET_SIZE);
+        transform.rotation = std::f32::consts::PI;
 
-    pub fn set_collided(&mut self, collided: bool) {
-        self.shape.collided = collided;
-    }
+        let entity = world.spawn(EntityPool::Bullets)?
+            .with(transform)
+            .with(Velocity {
+                linear: vec2(0.0, speed),
+                angular: 0.0,
+            })
+            .with(Collider {
+                shape_type: ShapeType::Square,
+                layer: CollisionLayer::EnemyProjectile,
+            })
+            .with(Health { hit_points: 1 })
+            .with(Team::Enemy)
+            .with(Lifetime { remaining: BULLET_LIFETIME })
+            .with(Sprite {
+                texture: SpriteTexture::Bullet,
+                animation: SpritesConfig::get_bullet_sprite(),
+                draw_size: vec2(ENEMY_BULLET_SIZE, ENEMY_BULLET_SIZE),
+                elapsed: 0.0,
+            })
+            .build();
 
-    pub fn update(&mut self, delta_time: f32) {
-        self.shape.position.y -= self.shape.speed * delta_time;
+        Some(entity)
     }
 
-    pub fn draw(&self, sprites_config: &SpritesConfig) {
-        let bullet_frame = self.sprite.frame();
+    /// Sends a bolt of the hero diagonally up, to the left when `left`.
+    pub fn ricochet(world: &mut World, entity: Entity, left: bool) {
+        let direction = if left { -1.0 } else { 1.0 };
+
+        if let Some(velocity) = world.velocities.get_mut(entity) {
+            let speed = velocity.linear.length() * FRAC_1_SQRT_2;
+            velocity.linear = vec2(speed * direction, -speed);
+        }
+        if let Some(transform) = world.transforms.get_mut(entity) {
+            transform.rotation = FRAC_PI_4 * direction;
+        }
+    }
 
-        let size = self.size();
+    /// Bounces the bolts of the hero moving sideways off the sides of a play
+    /// area `width` pixels wide.
+    pub fn bounce_off_walls(world: &mut World, width: f32) {
+        for (entity, velocity) in world.velocities.iter_mut() {
+            if entity.pool() != EntityPool::Bullets || velocity.linear.x == 0.0 || world.teams.get(entity) != Some(&Team::Hero) {
+                continue;
+            }
+            let Some(transform) = world.transforms.get_mut(entity) else {
+                continue;
+            };
 
-        draw_texture_ex(
-            &sprites_config.bullet_texture,
-            self.shape.position.x - size / 2.0,
-            self.shape.position.y - size / 2.0,
-            WHITE,
-            DrawTextureParams {
-                dest_size: Some(vec2(size, size)),
-                source: Some(bullet_frame.source_rect),
-                ..Default::default()
-            },
-        );
+            let half = transform.size / 2.0;
+            if (transform.position.x < half && velocity.linear.x < 0.0) || (transform.position.x > width - half && velocity.linear.x > 0.0) {
+                velocity.linear.x = -velocity.linear.x;
+                transform.rotation = -transform.rotation;
+            }
+        }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,74 @@
+use std::sync::OnceLock;
+
+/// Enemies a campaign level sends at a point in time.
+#[derive(Clone, Copy, Debug)]
+pub enum LevelSpawn {
+    /// Enemies spread evenly across the screen.
+    Row { count: u32, size: f32, speed: f32 },
+    Boss { size: f32, hit_points: u32, speed: f32 },
+}
+
+#[derive(Clone, Debug)]
+pub struct Level {
+    pub name: String,
+    /// Seconds into the level of each spawn, in order.
+    pub spawns: Vec<(f32, LevelSpawn)>,
+}
+
+impl Level {
+    pub fn has_boss(&self) -> bool {
+        self.spawns.iter().any(|(_, spawn)| matches!(spawn, LevelSpawn::Boss { .. }))
+    }
+
+    /// Reads the levels of `assets/campaign.txt`, a `[name]` line followed
+    /// by the spawns of each level.
+    pub fn parse_all(text: &str) -> Result<Vec<Level>, String> {
+        let mut levels: Vec<Level> = Vec::new();
+
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            let error = |message: &str| format!("line {}: {}", index + 1, message);
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+                levels.push(Level {
+                    name: name.to_string(),
+                    spawns: Vec::new(),
+                });
+                continue;
+            }
+
+            let level = levels.last_mut().ok_or_else(|| error("spawn before the first [name]"))?;
+            let mut fields = line.split(' ');
+            let kind = fields.next().unwrap_or_default();
+            let values = fields.map(str::parse::<f32>).collect::<Result<Vec<_>, _>>().map_err(|_| error("invalid number"))?;
+
+            let spawn = match (kind, values.as_slice()) {
+                ("row", &[time, count, size, speed]) => (time, LevelSpawn::Row { count: count as u32, size, speed }),
+                ("boss", &[time, size, hit_points, speed]) => (time, LevelSpawn::Boss { size, hit_points: hit_points as u32, speed }),
+                ("row" | "boss", _) => return Err(error("wrong number of values")),
+                _ => return Err(error("unknown spawn")),
+            };
+
+            if level.spawns.last().is_some_and(|(last, _)| spawn.0 < *last) {
+                return Err(error("spawns out of order"));
+            }
+            level.spawns.push(spawn);
+        }
+
+        match levels.iter().find(|level| level.spawns.is_empty()) {
+            Some(level) => Err(format!("{} has no spawns", level.name)),
+            None => Ok(levels),
+        }
+    }
+}
+
+/// The campaign levels, in order.
+pub fn levels() -> &'static [Level] {
+    static LEVELS: OnceLock<Vec<Level>> = OnceLock::new();
+
+    LEVELS.get_or_init(|| Level::parse_all(include_str!("../assets/campaign.txt")).expect("invalid assets/campaign.txt"))
+}
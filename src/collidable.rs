@@ -1,35 +1,261 @@
-use macroquad::prelude::Rect;
+use macroquad::prelude::{vec2, Rect, Vec2};
 
-use crate::shape::ShapeType;
+use crate::{collision_layers::{CollisionLayer, CollisionLayers}, collision_mask::MaskedSprite, shape::ShapeType, sprites_config::SpriteMasks};
+
+/// Maximum number of positions sampled along a step by the oriented box and
+/// pixel tests.
+pub(crate) const MAX_SWEEP_SAMPLES: usize = 32;
 
 pub trait Collidable {
 
     fn shape_type(&self) -> ShapeType;
     fn position(&self) -> macroquad::prelude::Vec2;
     fn size(&self) -> f32;
+    fn layer(&self) -> CollisionLayer;
 
-    fn rect(&self) -> Rect {
+    /// Layers this entity collides with.
+    fn mask(&self) -> CollisionLayers {
+        self.layer().default_mask()
+    }
+
+    /// Whether the entity still takes part in collisions this step.
+    fn is_active(&self) -> bool {
+        true
+    }
+
+    /// Called once for every entity this one collided with.
+    fn on_hit(&mut self, _other: CollisionLayer) {}
+
+    /// Drawn frame used for the precise test after a bounding box hit.
+    fn pixel_mask<'a>(&self, _masks: &'a SpriteMasks) -> Option<MaskedSprite<'a>> {
+        None
+    }
+
+    fn interacts_with<T: Collidable>(&self, other: &T) -> bool {
+        self.mask().contains(other.layer()) || other.mask().contains(self.layer())
+    }
+
+    /// Position at the start of the current step, for objects that move.
+    fn previous_position(&self) -> Vec2 {
+        self.position()
+    }
+
+    /// Rotation in radians around the position, clockwise on screen.
+    fn rotation(&self) -> f32 {
+        0.0
+    }
+
+    /// Side of the axis-aligned box enclosing the rotated shape.
+    ///
+    /// Every shape collides as a box, circles included, so it turns with the
+    /// sprite and its pixel mask.
+    fn bounding_size(&self) -> f32 {
+        let rotation = self.rotation();
+        if rotation == 0.0 {
+            return self.size();
+        }
+
+        self.size() * (rotation.cos().abs() + rotation.sin().abs())
+    }
+
+    /// Corners of the oriented box when centered on `center`.
+    fn corners(&self, center: Vec2) -> [Vec2; 4] {
+        let half = self.size() / 2.0;
+        let axis = Vec2::from_angle(self.rotation());
+
+        [vec2(-half, -half), vec2(half, -half), vec2(half, half), vec2(-half, half)]
+            .map(|corner| center + axis.rotate(corner))
+    }
+
+    /// Separating axis test between both oriented boxes, each centered on
+    /// the given position.
+    fn oriented_overlaps_at<T: Collidable>(&self, center: Vec2, other: &T, other_center: Vec2) -> bool {
+        let corners = self.corners(center);
+        let other_corners = other.corners(other_center);
+
+        let axes = [
+            corners[1] - corners[0],
+            corners[3] - corners[0],
+            other_corners[1] - other_corners[0],
+            other_corners[3] - other_corners[0],
+        ];
+
+        axes.iter().all(|axis| {
+            let project = |points: &[Vec2; 4]| {
+                points.iter().fold((f32::MAX, f32::MIN), |(min, max), point| {
+                    let projection = point.dot(*axis);
+                    (min.min(projection), max.max(projection))
+                })
+            };
+            let (min, max) = project(&corners);
+            let (other_min, other_max) = project(&other_corners);
+
+            min < other_max && other_min < max
+        })
+    }
+
+    /// Confirms a swept bounding box contact with the oriented boxes, sampled
+    /// along the part of the step where the bounding boxes overlap.
+    fn oriented_overlaps<T: Collidable>(&self, other: &T, (enter, exit): (f32, f32)) -> bool {
+        // Axis-aligned boxes are already exact
+        if self.bounding_size() == self.size() && other.bounding_size() == other.size() {
+            return true;
+        }
+
+        let motion = self.position() - self.previous_position();
+        let other_motion = other.position() - other.previous_position();
+
+        let travelled = (motion - other_motion).length() * (exit - enter);
+        let steps = (travelled.ceil() as usize).clamp(1, MAX_SWEEP_SAMPLES);
+
+        (0..=steps).any(|step| {
+            let time = enter + (exit - enter) * step as f32 / steps as f32;
+            self.oriented_overlaps_at(
+                self.previous_position() + motion * time,
+                other,
+                other.previous_position() + other_motion * time,
+            )
+        })
+    }
+
+    /// Box centered on the position before rotation, matching how the
+    /// shapes and their sprites are drawn.
+    fn local_rect(&self) -> Rect {
         let pos = self.position();
         let size = self.size();
-        let shape_type = self.shape_type();
 
-        match shape_type {
-            ShapeType::Circle => Rect {
-                x: pos.x - size / 2.0,
-                y: pos.y - size / 2.0,
-                w: size,
-                h: size,
-            },
-            _ => Rect {
-                x: pos.x,
-                y: pos.y,
-                w: size,
-                h: size,
-            },
+        Rect {
+            x: pos.x - size / 2.0,
+            y: pos.y - size / 2.0,
+            w: size,
+            h: size,
+        }
+    }
+
+    /// Axis-aligned bounding box, enclosing the shape when it is rotated.
+    fn rect(&self) -> Rect {
+        let pos = self.position();
+        let size = self.bounding_size();
+
+        Rect {
+            x: pos.x - size / 2.0,
+            y: pos.y - size / 2.0,
+            w: size,
+            h: size,
         }
     }
 
+    /// Area covered by the bounding box during the current step.
+    fn swept_rect(&self) -> Rect {
+        let rect = self.rect();
+        rect.combine_with(rect.offset(self.previous_position() - self.position()))
+    }
+
+    /// Swept test between both bounding boxes over the current step.
+    ///
+    /// Returns the fraction of the step at which the boxes start and stop
+    /// overlapping, so fast objects can't skip over each other.
+    fn sweep<T: Collidable>(&self, other: &T) -> Option<(f32, f32)> {
+        let start = self.previous_position() - other.previous_position();
+        let motion = (self.position() - self.previous_position()) - (other.position() - other.previous_position());
+        let half_size = (self.bounding_size() + other.bounding_size()) / 2.0;
+
+        let mut enter = 0.0f32;
+        let mut exit = 1.0f32;
+
+        for axis in 0..2 {
+            if motion[axis].abs() < f32::EPSILON {
+                if start[axis].abs() >= half_size {
+                    return None;
+                }
+                continue;
+            }
+
+            let t0 = (-half_size - start[axis]) / motion[axis];
+            let t1 = (half_size - start[axis]) / motion[axis];
+
+            enter = enter.max(t0.min(t1));
+            exit = exit.min(t0.max(t1));
+
+            if enter > exit {
+                return None;
+            }
+        }
+
+        Some((enter, exit))
+    }
+
     fn collides_with<T: Collidable>(&self, other: &T) -> bool {
-        self.rect().overlaps(&other.rect())
+        self.sweep(other).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_4;
+
+    use super::*;
+
+    struct Body {
+        shape_type: ShapeType,
+        position: Vec2,
+        size: f32,
+        rotation: f32,
+    }
+
+    impl Collidable for Body {
+        fn shape_type(&self) -> ShapeType {
+            self.shape_type
+        }
+
+        fn position(&self) -> Vec2 {
+            self.position
+        }
+
+        fn size(&self) -> f32 {
+            self.size
+        }
+
+        fn layer(&self) -> CollisionLayer {
+            CollisionLayer::Enemy
+        }
+
+        fn rotation(&self) -> f32 {
+            self.rotation
+        }
+    }
+
+    fn rotated(shape_type: ShapeType, position: Vec2) -> Body {
+        Body { shape_type, position, size: 10.0, rotation: FRAC_PI_4 }
+    }
+
+    #[test]
+    fn rect_is_centered_on_the_position() {
+        let square = Body { rotation: 0.0, ..rotated(ShapeType::Square, vec2(100.0, 50.0)) };
+
+        assert_eq!(square.rect(), Rect::new(95.0, 45.0, 10.0, 10.0));
+        assert_eq!(square.local_rect(), square.rect());
+    }
+
+    #[test]
+    fn every_shape_turns_with_its_rotation() {
+        for shape_type in [ShapeType::Square, ShapeType::Circle] {
+            let shape = rotated(shape_type, Vec2::ZERO);
+            let half_diagonal = 5.0 * std::f32::consts::SQRT_2;
+
+            assert!((shape.bounding_size() - 2.0 * half_diagonal).abs() < 1e-4);
+            assert!((shape.corners(Vec2::ZERO)[0] - vec2(0.0, -half_diagonal)).length() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn oriented_boxes_miss_in_the_corners_of_their_bounds() {
+        let shape = rotated(ShapeType::Circle, Vec2::ZERO);
+        let corner = Body { rotation: 0.0, size: 2.0, ..rotated(ShapeType::Square, vec2(6.0, 6.0)) };
+        let side = Body { rotation: 0.0, size: 2.0, ..rotated(ShapeType::Square, vec2(6.0, 0.0)) };
+
+        assert!(shape.sweep(&corner).is_some());
+        assert!(!shape.oriented_overlaps_at(shape.position(), &corner, corner.position()));
+        assert!(shape.oriented_overlaps_at(shape.position(), &side, side.position()));
     }
-}
\ No newline at end of file
+}
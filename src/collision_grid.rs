@@ -0,0 +1,162 @@
+use macroquad::math::Rect;
+
+use crate::{collidable::Collidable, sprites_config::SpriteMasks};
+
+/// A colliding pair, with the fraction of the step during which the two
+/// bounding boxes overlap.
+#[derive(Debug, Clone, Copy)]
+pub struct Contact {
+    pub source: usize,
+    pub target: usize,
+    pub enter: f32,
+    pub exit: f32,
+}
+
+/// Uniform grid used as a broad-phase for collision checks.
+///
+/// Bodies are bucketed by the cells their swept bounding rect touches, then
+/// every body only tests the others sharing a cell with it. The buffers are
+/// kept between frames so rebuilding the grid each step does not allocate.
+pub struct CollisionGrid {
+    cell_size: f32,
+    cols: usize,
+    rows: usize,
+    cells: Vec<Vec<usize>>,
+    last_source: Vec<usize>,
+    contacts: Vec<Contact>,
+}
+
+impl Default for CollisionGrid {
+    fn default() -> Self {
+        Self::new(64.0)
+    }
+}
+
+impl CollisionGrid {
+    pub fn new(cell_size: f32) -> Self {
+        CollisionGrid {
+            cell_size,
+            cols: 0,
+            rows: 0,
+            cells: Vec::new(),
+            last_source: Vec::new(),
+            contacts: Vec::new(),
+        }
+    }
+
+    fn cell_range(&self, rect: &Rect) -> (usize, usize, usize, usize) {
+        let to_col = |x: f32| ((x / self.cell_size).floor().max(0.0) as usize).min(self.cols - 1);
+        let to_row = |y: f32| ((y / self.cell_size).floor().max(0.0) as usize).min(self.rows - 1);
+
+        (to_col(rect.x), to_row(rect.y), to_col(rect.right()), to_row(rect.bottom()))
+    }
+
+    /// Buckets the bodies for the given area. Objects outside the area are
+    /// clamped into the border cells.
+    pub fn rebuild<T: Collidable>(&mut self, width: f32, height: f32, bodies: &[T]) {
+        self.cols = ((width / self.cell_size).ceil() as usize).max(1);
+        self.rows = ((height / self.cell_size).ceil() as usize).max(1);
+
+        let cell_count = self.cols * self.rows;
+        self.cells.resize_with(cell_count, Vec::new);
+        self.cells.truncate(cell_count);
+        for cell in self.cells.iter_mut() {
+            cell.clear();
+        }
+
+        for (index, body) in bodies.iter().enumerate() {
+            let (min_col, min_row, max_col, max_row) = self.cell_range(&body.swept_rect());
+            for row in min_row..=max_row {
+                for col in min_col..=max_col {
+                    self.cells[row * self.cols + col].push(index);
+                }
+            }
+        }
+
+        self.last_source.clear();
+        self.last_source.resize(bodies.len(), usize::MAX);
+    }
+
+    /// Finds every pair of bodies whose layers interact and whose swept
+    /// bounding boxes overlap, in a single pass ordered by the time of impact
+    /// within the step. `bodies` must be the slice the grid was rebuilt with.
+    pub fn find_contacts<T: Collidable>(&mut self, bodies: &[T]) -> &[Contact] {
+        self.contacts.clear();
+
+        for (source_index, source) in bodies.iter().enumerate() {
+            let (min_col, min_row, max_col, max_row) = self.cell_range(&source.swept_rect());
+            for row in min_row..=max_row {
+                for col in min_col..=max_col {
+                    for &target_index in self.cells[row * self.cols + col].iter() {
+                        // Each pair is only tested once, from its lowest index, and a
+                        // body spanning several cells is only tested once per source
+                        if target_index <= source_index || self.last_source[target_index] == source_index {
+                            continue;
+                        }
+                        self.last_source[target_index] = source_index;
+
+                        let target = &bodies[target_index];
+                        if !source.interacts_with(target) {
+                            continue;
+                        }
+
+                        if let Some((enter, exit)) = source.sweep(target) {
+                            self.contacts.push(Contact {
+                                source: source_index,
+                                target: target_index,
+                                enter,
+                                exit,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        // Sources are tested in order, so resetting lets the next query reuse the marks
+        self.last_source.fill(usize::MAX);
+
+        self.contacts.sort_unstable_by(|a, b| a.enter.total_cmp(&b.enter));
+
+        &self.contacts
+    }
+
+    /// Resolves every collision between the bodies through their collision
+    /// layers, calling `on_hit` on both sides of each contact, and returns
+    /// the contacts that hit.
+    ///
+    /// Bodies that stop being active after a hit don't take part in later
+    /// contacts of the same step. Bounding box hits are confirmed with the
+    /// oriented boxes, then with the pixel masks when `masks` are given.
+    pub fn resolve<T: Collidable>(&mut self, bodies: &mut [T], masks: Option<&SpriteMasks>) -> &[Contact] {
+        self.find_contacts(bodies);
+
+        self.contacts.retain(|contact| {
+            let (head, tail) = bodies.split_at_mut(contact.target);
+            let (source, target) = (&mut head[contact.source], &mut tail[0]);
+            if !source.is_active() || !target.is_active() {
+                return false;
+            }
+
+            let window = (contact.enter, contact.exit);
+            if !source.oriented_overlaps(target, window) {
+                return false;
+            }
+
+            if let Some(masks) = masks
+                && let (Some(source_mask), Some(target_mask)) = (source.pixel_mask(masks), target.pixel_mask(masks))
+                && !source_mask.overlaps(&target_mask, window)
+            {
+                return false;
+            }
+
+            let (source_layer, target_layer) = (source.layer(), target.layer());
+            source.on_hit(target_layer);
+            target.on_hit(source_layer);
+
+            true
+        });
+
+        &self.contacts
+    }
+}
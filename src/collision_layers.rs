@@ -0,0 +1,47 @@
+/// What an entity is, as far as collisions are concerned.
+#[derive(PartialEq, Eq, Default, Clone, Copy, Debug)]
+pub enum CollisionLayer {
+    Hero,
+    HeroProjectile,
+    Enemy,
+    EnemyProjectile,
+    Pickup,
+    #[default]
+    Terrain,
+}
+
+/// Set of layers an entity collides with.
+#[derive(PartialEq, Eq, Default, Clone, Copy, Debug)]
+pub struct CollisionLayers(u8);
+
+impl CollisionLayers {
+    pub const NONE: CollisionLayers = CollisionLayers(0);
+
+    pub const fn with(self, layer: CollisionLayer) -> Self {
+        CollisionLayers(self.0 | layer.bit())
+    }
+
+    pub const fn contains(self, layer: CollisionLayer) -> bool {
+        self.0 & layer.bit() != 0
+    }
+}
+
+impl CollisionLayer {
+    const fn bit(self) -> u8 {
+        1 << self as u8
+    }
+
+    /// The collision table: which layers each layer interacts with.
+    pub const fn default_mask(self) -> CollisionLayers {
+        use CollisionLayer::*;
+
+        match self {
+            Hero => CollisionLayers::NONE.with(Enemy).with(EnemyProjectile).with(Pickup).with(Terrain),
+            HeroProjectile => CollisionLayers::NONE.with(Enemy).with(Terrain),
+            Enemy => CollisionLayers::NONE.with(Hero).with(HeroProjectile),
+            EnemyProjectile => CollisionLayers::NONE.with(Hero).with(Terrain),
+            Pickup => CollisionLayers::NONE.with(Hero),
+            Terrain => CollisionLayers::NONE.with(Hero).with(HeroProjectile).with(EnemyProjectile),
+        }
+    }
+}
@@ -0,0 +1,239 @@
+use macroquad::{math::{vec2, Rect, Vec2}, texture::Image};
+
+use crate::collidable::MAX_SWEEP_SAMPLES;
+
+/// Axis-aligned box enclosing `rect` rotated around its center.
+fn rotated_bounds(rect: Rect, rotation: f32) -> Rect {
+    if rotation == 0.0 {
+        return rect;
+    }
+
+    let (sin, cos) = rotation.sin_cos();
+    let width = rect.w * cos.abs() + rect.h * sin.abs();
+    let height = rect.w * sin.abs() + rect.h * cos.abs();
+    let center = rect.center();
+
+    Rect::new(center.x - width / 2.0, center.y - height / 2.0, width, height)
+}
+
+/// Opaque pixels of a single sprite frame.
+#[derive(Debug, Default, Clone)]
+pub struct CollisionMask {
+    width: usize,
+    height: usize,
+    solid: Vec<bool>,
+}
+
+impl CollisionMask {
+    fn from_frame(image: &Image, frame: Rect) -> Self {
+        let width = frame.w as usize;
+        let height = frame.h as usize;
+        let mut solid = Vec::with_capacity(width * height);
+
+        for y in 0..height {
+            for x in 0..width {
+                let pixel = image.get_pixel(frame.x as u32 + x as u32, frame.y as u32 + y as u32);
+                solid.push(pixel.a > 0.0);
+            }
+        }
+
+        CollisionMask { width, height, solid }
+    }
+
+    /// Checks the pixel under `point` when the frame is drawn stretched over
+    /// `dest` and rotated around its center.
+    pub fn is_solid_at(&self, dest: Rect, rotation: f32, point: Vec2) -> bool {
+        let center = dest.center();
+        let point = center + Vec2::from_angle(-rotation).rotate(point - center);
+
+        if self.width == 0 || self.height == 0 || !dest.contains(point) {
+            return false;
+        }
+
+        let x = ((point.x - dest.x) / dest.w * self.width as f32) as usize;
+        let y = ((point.y - dest.y) / dest.h * self.height as f32) as usize;
+
+        self.solid[y.min(self.height - 1) * self.width + x.min(self.width - 1)]
+    }
+
+}
+
+/// A frame mask placed where it is drawn, along with how far it moved during
+/// the current step.
+pub struct MaskedSprite<'a> {
+    pub mask: &'a CollisionMask,
+    pub dest: Rect,
+    /// Collision box before rotation. Pixels drawn outside of it don't
+    /// collide, so the mask never reaches further than the box.
+    pub bounds: Rect,
+    pub rotation: f32,
+    pub motion: Vec2,
+}
+
+impl MaskedSprite<'_> {
+    fn offset_at(&self, time: f32) -> Vec2 {
+        -self.motion * (1.0 - time)
+    }
+
+    /// Checks the pixel under `point` at `time` into the step.
+    fn is_solid_at(&self, time: f32, point: Vec2) -> bool {
+        let dest = self.dest.offset(self.offset_at(time));
+        let bounds = self.bounds.offset(self.offset_at(time));
+        let center = bounds.center();
+
+        bounds.contains(center + Vec2::from_angle(-self.rotation).rotate(point - center))
+            && self.mask.is_solid_at(dest, self.rotation, point)
+    }
+
+    /// Area the solid pixels can cover at `time` into the step.
+    fn area_at(&self, time: f32) -> Option<Rect> {
+        let area = self.dest.intersect(self.bounds)?;
+        Some(rotated_bounds(area.offset(self.offset_at(time)), self.rotation))
+    }
+
+    /// Precise test at `time` into the step, sampled once per screen pixel
+    /// of the area where both sprites may overlap.
+    fn overlaps_at(&self, other: &MaskedSprite, time: f32) -> bool {
+        let (Some(area), Some(other_area)) = (self.area_at(time), other.area_at(time)) else {
+            return false;
+        };
+        let Some(area) = area.intersect(other_area) else {
+            return false;
+        };
+
+        let mut y = area.y.floor();
+        while y < area.bottom() {
+            let mut x = area.x.floor();
+            while x < area.right() {
+                let point = vec2(x + 0.5, y + 0.5);
+                if self.is_solid_at(time, point) && other.is_solid_at(time, point) {
+                    return true;
+                }
+                x += 1.0;
+            }
+            y += 1.0;
+        }
+
+        false
+    }
+
+    /// Samples the pixel test along the part of the step where the bounding
+    /// boxes overlap, roughly once per pixel of relative movement.
+    pub fn overlaps(&self, other: &MaskedSprite, (enter, exit): (f32, f32)) -> bool {
+        let travelled = (self.motion - other.motion).length() * (exit - enter);
+        let steps = (travelled.ceil() as usize).clamp(1, MAX_SWEEP_SAMPLES);
+
+        (0..=steps).any(|step| {
+            let time = enter + (exit - enter) * step as f32 / steps as f32;
+            self.overlaps_at(other, time)
+        })
+    }
+}
+
+/// Collision masks for every frame of a sprite sheet.
+#[derive(Debug, Default, Clone)]
+pub struct SpriteMask {
+    frame_width: f32,
+    frame_height: f32,
+    cols: usize,
+    frames: Vec<CollisionMask>,
+}
+
+impl SpriteMask {
+    pub fn new(image: &Image, frame_width: u32, frame_height: u32) -> Self {
+        let cols = (image.width() as u32 / frame_width) as usize;
+        let rows = (image.height() as u32 / frame_height) as usize;
+        let (frame_width, frame_height) = (frame_width as f32, frame_height as f32);
+
+        let mut frames = Vec::with_capacity(cols * rows);
+        for row in 0..rows {
+            for col in 0..cols {
+                let frame = Rect::new(col as f32 * frame_width, row as f32 * frame_height, frame_width, frame_height);
+                frames.push(CollisionMask::from_frame(image, frame));
+            }
+        }
+
+        SpriteMask {
+            frame_width,
+            frame_height,
+            cols,
+            frames,
+        }
+    }
+
+    /// Mask of the frame an `AnimationFrame::source_rect` points at.
+    pub fn frame(&self, source_rect: Rect) -> &CollisionMask {
+        let col = (source_rect.x / self.frame_width) as usize;
+        let row = (source_rect.y / self.frame_height) as usize;
+
+        let index = (row * self.cols + col).min(self.frames.len() - 1);
+        &self.frames[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solid(width: usize, height: usize) -> CollisionMask {
+        CollisionMask { width, height, solid: vec![true; width * height] }
+    }
+
+    fn placed(mask: &CollisionMask, center: Vec2, draw_size: Vec2, size: f32) -> MaskedSprite<'_> {
+        MaskedSprite {
+            mask,
+            dest: Rect::new(center.x - draw_size.x / 2.0, center.y - draw_size.y / 2.0, draw_size.x, draw_size.y),
+            bounds: Rect::new(center.x - size / 2.0, center.y - size / 2.0, size, size),
+            rotation: 0.0,
+            motion: Vec2::ZERO,
+        }
+    }
+
+    #[test]
+    fn is_solid_at_reads_the_stretched_frame() {
+        let mask = CollisionMask { width: 2, height: 1, solid: vec![false, true] };
+        let dest = Rect::new(0.0, 0.0, 20.0, 10.0);
+
+        assert!(!mask.is_solid_at(dest, 0.0, vec2(5.0, 5.0)));
+        assert!(mask.is_solid_at(dest, 0.0, vec2(15.0, 5.0)));
+        assert!(!mask.is_solid_at(dest, 0.0, vec2(25.0, 5.0)));
+        // Half a turn swaps both halves
+        assert!(mask.is_solid_at(dest, std::f32::consts::PI, vec2(5.0, 5.0)));
+    }
+
+    #[test]
+    fn pixels_outside_the_collision_box_do_not_collide() {
+        let ship = solid(2, 3);
+        let bullet = solid(1, 1);
+        // Drawn 32x48 around a 32 px collision box, as the hero
+        let hero = placed(&ship, vec2(100.0, 100.0), vec2(32.0, 48.0), 32.0);
+
+        let above_the_box = placed(&bullet, vec2(100.0, 80.0), vec2(4.0, 4.0), 4.0);
+        assert!(!hero.overlaps(&above_the_box, (0.0, 1.0)));
+
+        let inside_the_box = placed(&bullet, vec2(100.0, 90.0), vec2(4.0, 4.0), 4.0);
+        assert!(hero.overlaps(&inside_the_box, (0.0, 1.0)));
+    }
+
+    #[test]
+    fn transparent_pixels_do_not_collide() {
+        let ring = CollisionMask { width: 3, height: 3, solid: vec![true, true, true, true, false, true, true, true, true] };
+        let dot = solid(1, 1);
+        let ring = placed(&ring, vec2(15.0, 15.0), vec2(30.0, 30.0), 30.0);
+
+        assert!(!ring.overlaps(&placed(&dot, vec2(15.0, 15.0), vec2(4.0, 4.0), 4.0), (0.0, 1.0)));
+        assert!(ring.overlaps(&placed(&dot, vec2(5.0, 15.0), vec2(4.0, 4.0), 4.0), (0.0, 1.0)));
+    }
+
+    #[test]
+    fn overlaps_are_found_along_the_step() {
+        let mask = solid(1, 1);
+        let still = placed(&mask, vec2(50.0, 50.0), vec2(4.0, 4.0), 4.0);
+        // Passes through `still` during the step, ending far past it
+        let mut moving = placed(&mask, vec2(50.0, 10.0), vec2(4.0, 4.0), 4.0);
+        moving.motion = vec2(0.0, -80.0);
+
+        assert!(moving.overlaps(&still, (0.0, 1.0)));
+        assert!(!moving.overlaps(&still, (0.9, 1.0)));
+    }
+}
@@ -0,0 +1,47 @@
+/// Seconds a chain lasts after its last kill.
+pub const COMBO_TIMEOUT: f32 = 2.5;
+/// Kills needed for each step of the multiplier.
+const KILLS_PER_MULTIPLIER: u32 = 5;
+pub const MAX_MULTIPLIER: u32 = 8;
+
+/// Chain of quick consecutive kills, multiplying the points of each kill.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct Combo {
+    /// Kills in the current chain.
+    pub chain: u32,
+    /// Seconds left to keep the chain going.
+    pub remaining: f32,
+}
+
+impl Combo {
+    pub fn multiplier(&self) -> u32 {
+        (1 + self.chain / KILLS_PER_MULTIPLIER).min(MAX_MULTIPLIER)
+    }
+
+    /// Share of the timeout left, from 0 to 1.
+    pub fn timer(&self) -> f32 {
+        self.remaining / COMBO_TIMEOUT
+    }
+
+    pub fn kill(&mut self) {
+        self.chain += 1;
+        self.remaining = COMBO_TIMEOUT;
+    }
+
+    /// Counts down the timeout, returns the chain it ended if any.
+    pub fn update(&mut self, delta_time: f32) -> Option<u32> {
+        if self.chain == 0 {
+            return None;
+        }
+
+        self.remaining -= delta_time;
+        (self.remaining <= 0.0).then(|| self.break_chain())
+    }
+
+    /// Ends the chain, returns how long it was.
+    pub fn break_chain(&mut self) -> u32 {
+        let chain = self.chain;
+        *self = Self::default();
+        chain
+    }
+}
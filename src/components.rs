@@ -0,0 +1,77 @@
+use macroquad::math::Vec2;
+
+use crate::{collision_layers::CollisionLayer, shape::ShapeType, sprites_config::SpriteAnimation};
+
+/// Where an entity is, centered on its position.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct Transform {
+    pub position: Vec2,
+    /// Position at the start of the current step, for swept collision tests.
+    pub previous_position: Vec2,
+    /// Rotation in radians around the position, clockwise on screen.
+    pub rotation: f32,
+    pub size: f32,
+}
+
+impl Transform {
+    pub fn new(position: Vec2, size: f32) -> Self {
+        Transform {
+            position,
+            previous_position: position,
+            rotation: 0.0,
+            size,
+        }
+    }
+}
+
+#[derive(Default, Clone, Copy, Debug)]
+pub struct Velocity {
+    /// Pixels per second.
+    pub linear: Vec2,
+    /// Radians per second.
+    pub angular: f32,
+}
+
+/// Sprite sheets loaded by `SpritesConfig`.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum SpriteTexture {
+    Ship,
+    Bullet,
+    EnemySmall,
+    EnemyMedium,
+    EnemyBig,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Sprite {
+    pub texture: SpriteTexture,
+    pub animation: SpriteAnimation,
+    /// Size drawn on screen, centered on the transform position.
+    pub draw_size: Vec2,
+    /// Seconds the animation has been playing.
+    pub elapsed: f32,
+}
+
+#[derive(Default, Clone, Copy, Debug)]
+pub struct Collider {
+    pub shape_type: ShapeType,
+    pub layer: CollisionLayer,
+}
+
+/// Hits an entity takes before being destroyed.
+#[derive(Clone, Copy, Debug)]
+pub struct Health {
+    pub hit_points: u32,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Team {
+    Hero,
+    Enemy,
+}
+
+/// Seconds before the entity is despawned.
+#[derive(Clone, Copy, Debug)]
+pub struct Lifetime {
+    pub remaining: f32,
+}
@@ -2,6 +2,14 @@ use macroquad::color::*;
 
 pub const MOVEMENT_SPEED: f32 = 200.0;
 pub const INITIAL_LIVES: u32 = 5;
+pub const PIXEL_PERFECT_COLLISIONS: bool = true;
+
+pub const MAX_BULLETS: usize = 256;
+pub const MAX_ENEMIES: usize = 256;
+pub const MAX_EXPLOSIONS: usize = 32;
+
+/// Seconds each wave of enemies lasts.
+pub const WAVE_DURATION: f32 = 30.0;
 
 pub const COLORS: [Color; 11] = [
     DARKPURPLE, BLUE, GREEN, RED, PURPLE, ORANGE, PINK, BROWN, GRAY,
@@ -9,4 +17,18 @@ pub const COLORS: [Color; 11] = [
 ];
 
 pub const ASSETS_PATH: &str = "assets";
-pub const HIGH_SCORE_FILE_PATH: &str = "high_score.dat";
\ No newline at end of file
+pub const REPLAYS_PATH: &str = "replays";
+
+/// Folder of the game in the platform data directory.
+pub const GAME_DIRECTORY_NAME: &str = "macroquad-tst";
+pub const SAVE_FILE_NAME: &str = "save.dat";
+/// Files of older versions, read once to migrate them to the save file.
+pub const HIGH_SCORE_FILE_PATH: &str = "high_score.dat";
+pub const LEADERBOARDS_FILE_PATH: &str = "leaderboards.dat";
+
+/// Default online leaderboard, overridden by the `LEADERBOARD_URL` variable.
+pub const ONLINE_LEADERBOARD_URL: &str = "http://127.0.0.1:7878";
+/// Submissions not sent to the online leaderboard yet.
+pub const ONLINE_QUEUE_FILE_NAME: &str = "online_queue.txt";
+/// Most submissions kept waiting, older ones are dropped past it.
+pub const MAX_ONLINE_QUEUE: usize = 20;
\ No newline at end of file
@@ -0,0 +1,53 @@
+use macroquad::{color::GREEN, text::draw_text, time::get_fps, window::screen_height};
+
+use crate::{director::Director, particles::Particles, world::{EntityPool, World}};
+
+/// Development information drawn over the game, toggled with F3.
+#[derive(Default)]
+pub struct DebugOverlay {
+    pub visible: bool,
+}
+
+impl DebugOverlay {
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+    }
+
+    fn lines(world: &World, director: &Director, particles: &Particles) -> Vec<String> {
+        let mut lines = vec![format!("FPS: {}", get_fps())];
+
+        for pool in EntityPool::ALL {
+            let (used, capacity) = world.occupancy(pool);
+            lines.push(format!("{:?}: {}/{}", pool, used, capacity));
+        }
+
+        lines.push(format!("Explosions: {}/{}", particles.explosions.len(), particles.explosions.capacity()));
+
+        lines.push(format!("Director: {:?} {:.1}s", director.phase, director.phase_time));
+        lines.push(format!("Intensity: {:.2}  Performance: {:.2}", director.intensity, director.performance));
+        lines.push(format!("Kill rate: {:.2}/s  Recent hits: {:.2}", director.kill_rate, director.recent_hits));
+        lines.push(format!("Since hit: {:.1}s", director.time_since_hit));
+        lines.push(format!(
+            "Spawn offset: {}  Min size: {:.0}  Speed: x{:.2}",
+            director.spawn_threshold_offset(),
+            director.min_enemy_size(),
+            director.enemy_speed(),
+        ));
+
+        lines
+    }
+
+    pub fn draw(&self, world: &World, director: &Director, particles: &Particles) {
+        if !self.visible {
+            return;
+        }
+
+        const FONT_SIZE: f32 = 20.0;
+        let lines = Self::lines(world, director, particles);
+        let top = screen_height() - FONT_SIZE * lines.len() as f32;
+
+        for (index, line) in lines.iter().enumerate() {
+            draw_text(line, 10.0, top + FONT_SIZE * index as f32, FONT_SIZE, GREEN);
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use crate::constants::INITIAL_LIVES;
+
+/// Preset chosen before a run, scaling how hard and how rewarding it is.
+#[derive(PartialEq, Eq, Hash, Default, Clone, Copy, Debug)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    Insane,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard, Difficulty::Insane];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+            Difficulty::Insane => "Insane",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|difficulty| difficulty.name() == name)
+    }
+
+    /// An enemy spawns on frames where a roll from 0 to 98 reaches this.
+    pub fn spawn_threshold(&self) -> i32 {
+        match self {
+            Difficulty::Easy => 97,
+            Difficulty::Normal => 95,
+            Difficulty::Hard => 93,
+            Difficulty::Insane => 90,
+        }
+    }
+
+    /// Factor of the speed of the enemies.
+    pub fn enemy_speed(&self) -> f32 {
+        match self {
+            Difficulty::Easy => 0.75,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 1.25,
+            Difficulty::Insane => 1.5,
+        }
+    }
+
+    /// Shots per second of each enemy.
+    pub fn enemy_fire_rate(&self) -> f32 {
+        match self {
+            Difficulty::Easy | Difficulty::Normal => 0.0,
+            Difficulty::Hard => 0.15,
+            Difficulty::Insane => 0.3,
+        }
+    }
+
+    pub fn starting_lives(&self) -> u32 {
+        match self {
+            Difficulty::Easy => 7,
+            Difficulty::Normal => INITIAL_LIVES,
+            Difficulty::Hard => 4,
+            Difficulty::Insane => 3,
+        }
+    }
+
+    /// Factor of the points of every kill.
+    pub fn score_multiplier(&self) -> f32 {
+        match self {
+            Difficulty::Easy => 0.5,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 1.5,
+            Difficulty::Insane => 2.0,
+        }
+    }
+}
@@ -0,0 +1,126 @@
+/// Seconds over which kills and hits are remembered.
+const MEMORY: f32 = 20.0;
+/// Seconds without a hit for the full calm part of the performance.
+const CALM_TIME: f32 = 30.0;
+/// Kills per second for the full kill part of the performance.
+const GOOD_KILL_RATE: f32 = 1.0;
+/// Intensity gained per second while building up.
+const BUILD_UP_SPEED: f32 = 0.04;
+/// Intensity lost per second during a relief.
+const RELIEF_SPEED: f32 = 0.12;
+const PEAK_DURATION: f32 = 8.0;
+/// Seconds of a relief for an average performance, longer for a struggling
+/// player and shorter for one doing well.
+const RELIEF_DURATION: f32 = 10.0;
+const RELIEF_INTENSITY: f32 = 0.15;
+/// Recent hits that cut a build up or a peak short.
+const BREAKING_POINT: f32 = 2.0;
+
+/// Part of the tension and relief cycle the director is in.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum DirectorPhase {
+    /// Intensity rises towards a peak set by the performance.
+    BuildUp,
+    /// Intensity holds at its peak.
+    Peak,
+    /// Intensity falls to let the player recover.
+    Relief,
+}
+
+/// Adjusts the spawns to how well the player is doing, in cycles of
+/// tension and relief.
+///
+/// It only uses plain arithmetic on the state of the run, so replays play
+/// it the same on every machine.
+#[derive(Clone, Copy, Debug)]
+pub struct Director {
+    pub phase: DirectorPhase,
+    /// Seconds since the phase started.
+    pub phase_time: f32,
+    /// From 0 to 1, how hard the spawns push.
+    pub intensity: f32,
+    /// From 0 to 1, how well the player is doing.
+    pub performance: f32,
+    /// Kills per second, averaged over the last seconds.
+    pub kill_rate: f32,
+    /// Hits taken, fading over the last seconds.
+    pub recent_hits: f32,
+    pub time_since_hit: f32,
+}
+
+impl Default for Director {
+    fn default() -> Self {
+        Director {
+            phase: DirectorPhase::BuildUp,
+            phase_time: 0.0,
+            intensity: 0.3,
+            performance: 0.5,
+            kill_rate: 0.0,
+            recent_hits: 0.0,
+            time_since_hit: 0.0,
+        }
+    }
+}
+
+impl Director {
+    /// Added to the spawn threshold of the difficulty, from 3 when calm to
+    /// -3 at full intensity.
+    pub fn spawn_threshold_offset(&self) -> i32 {
+        (3.0 - 6.0 * self.intensity).round() as i32
+    }
+
+    /// Smallest size of new enemies, more of them big as intensity rises.
+    pub fn min_enemy_size(&self) -> f32 {
+        16.0 + 24.0 * self.intensity
+    }
+
+    /// Factor of the speed of new enemies.
+    pub fn enemy_speed(&self) -> f32 {
+        0.85 + 0.35 * self.intensity
+    }
+
+    fn enter(&mut self, phase: DirectorPhase) {
+        self.phase = phase;
+        self.phase_time = 0.0;
+    }
+
+    /// Takes in the `kills` and whether the ship was `hit` during the last
+    /// `delta_time` seconds, and moves through the cycle.
+    pub fn update(&mut self, delta_time: f32, kills: u32, hit: bool, lives: u32, starting_lives: u32) {
+        let fade = (1.0 - delta_time / MEMORY).max(0.0);
+        self.kill_rate = self.kill_rate * fade + kills as f32 / MEMORY;
+        self.recent_hits = self.recent_hits * fade + if hit { 1.0 } else { 0.0 };
+        self.time_since_hit = if hit { 0.0 } else { self.time_since_hit + delta_time };
+
+        let calm = (self.time_since_hit / CALM_TIME).min(1.0);
+        let kills = (self.kill_rate / GOOD_KILL_RATE).min(1.0);
+        let lives = lives as f32 / starting_lives.max(1) as f32;
+        self.performance = (0.4 * calm + 0.3 * kills + 0.3 * lives - 0.25 * self.recent_hits).clamp(0.0, 1.0);
+
+        self.phase_time += delta_time;
+        match self.phase {
+            DirectorPhase::BuildUp => {
+                let peak = 0.4 + 0.6 * self.performance;
+                self.intensity = (self.intensity + BUILD_UP_SPEED * delta_time).min(1.0);
+                if self.intensity >= peak {
+                    self.enter(DirectorPhase::Peak);
+                }
+            }
+            DirectorPhase::Peak => {
+                if self.phase_time >= PEAK_DURATION {
+                    self.enter(DirectorPhase::Relief);
+                }
+            }
+            DirectorPhase::Relief => {
+                self.intensity = (self.intensity - RELIEF_SPEED * delta_time).max(RELIEF_INTENSITY);
+                if self.phase_time >= RELIEF_DURATION * (1.5 - self.performance) {
+                    self.enter(DirectorPhase::BuildUp);
+                }
+            }
+        }
+
+        if self.recent_hits >= BREAKING_POINT && self.phase != DirectorPhase::Relief {
+            self.enter(DirectorPhase::Relief);
+        }
+    }
+}
@@ -0,0 +1,28 @@
+#version 100
+
+// Covers the screen with black cells, each one appearing once `progress`
+// passes its random threshold.
+
+precision mediump float;
+
+varying vec2 uv;
+
+uniform vec2 iResolution;
+uniform float progress;
+
+#define CELL_SIZE 4.
+
+float Hash21(vec2 p) {
+    return fract(sin(dot(p, vec2(12.9898, 78.233))) * 43758.5453);
+}
+
+void main()
+{
+    vec2 cell = floor(uv * iResolution / CELL_SIZE);
+
+    if (Hash21(cell) >= progress) {
+        discard;
+    }
+
+    gl_FragColor = vec4(0., 0., 0., 1.);
+}
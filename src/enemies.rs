@@ -1,12 +1,39 @@
-use macroquad::{prelude::animation::AnimatedSprite, rand, window::screen_height};
-
-use crate::{sprites_config::SpritesConfig, collidable::Collidable, enemy::Enemy};
+use macroquad::{math::{vec2, Vec2}, rand::RandGenerator};
+
+use crate::{bullet::Bullet, campaign::{self, LevelSpawn}, constants::WAVE_DURATION, director::Director, enemy::Enemy, mutators::Mutator, run_rules::RunRules, world::{Entity, EntityPool, World}};
+
+/// Pixels per second of the enemies' bolts.
+const ENEMY_BULLET_SPEED: f32 = 250.0;
+/// Height bosses come down to before swaying, in pixels from the top.
+const BOSS_HOLD_HEIGHT: f32 = 140.0;
+/// Seconds between the volleys of a boss.
+const BOSS_FIRE_INTERVAL: f32 = 1.5;
+/// The spawn thresholds were tuned for one roll a frame at 60 fps.
+const SPAWN_ROLLS_PER_SECOND: f32 = 60.0;
+
+/// A boss of the current level.
+#[derive(Clone, Copy, Debug)]
+pub struct Boss {
+    pub entity: Entity,
+    pub hit_points: u32,
+    pub max_hit_points: u32,
+    speed: f32,
+    fire_time: f32,
+}
 
+/// Decides when new enemies enter the screen and when they fire, from
+/// random spawns or from the campaign levels.
 pub struct Enemies {
-    list: Vec<Enemy>,
-    sprite_small: AnimatedSprite,
-    sprite_medium: AnimatedSprite,
-    sprite_big: AnimatedSprite,
+    /// Current wave, starting at 1. In the campaign, the current level.
+    pub wave: u32,
+    wave_time: f32,
+    /// Next spawn of the current level.
+    next_spawn: usize,
+    /// Whether the last campaign level was cleared.
+    pub completed: bool,
+    pub bosses: Vec<Boss>,
+    /// Where the enemies firing this step are.
+    shooters: Vec<Vec2>,
 }
 
 impl Default for Enemies {
@@ -18,68 +45,201 @@ impl Default for Enemies {
 impl Enemies {
     pub fn new() -> Self {
         Enemies {
-            list: Vec::new(),
-            sprite_small: SpritesConfig::get_enemy_small_sprite(),
-            sprite_medium: SpritesConfig::get_enemy_medium_sprite(),
-            sprite_big: SpritesConfig::get_enemy_big_sprite(),
+            wave: 1,
+            wave_time: 0.0,
+            next_spawn: 0,
+            completed: false,
+            bosses: Vec::new(),
+            shooters: Vec::new(),
+        }
+    }
+
+    pub fn restart(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Skips to `wave`, or in the campaign to level `wave`, at its boss when
+    /// `boss` is set.
+    pub fn start_at(&mut self, wave: u32, boss: bool) {
+        self.wave = wave;
+
+        let level = campaign::levels().get(wave as usize - 1);
+        if boss && let Some(level) = level && let Some(index) = level.spawns.iter().position(|(_, spawn)| matches!(spawn, LevelSpawn::Boss { .. })) {
+            self.next_spawn = index;
+            self.wave_time = level.spawns[index].0;
         }
     }
 
-    fn try_add_enemy(&mut self) {
-        if rand::gen_range(0, 99) >= 95 {
-            self.list.push(Enemy::new());
+    /// The difficulty and the mode set the base spawn chance and speed,
+    /// which the director raises or lowers.
+    ///
+    /// The chance is per second of the step, so the spawn rate doesn't depend
+    /// on the frame rate or the time scale.
+    fn try_add_enemy(&mut self, world: &mut World, rng: &RandGenerator, delta_time: f32, width: f32, rules: RunRules, director: &Director) {
+        let threshold = rules.difficulty.spawn_threshold() + rules.mode.spawn_threshold_offset(self.wave) + director.spawn_threshold_offset();
+        let chance = (99 - threshold.min(98)) as f32 / 99.0 * SPAWN_ROLLS_PER_SECOND;
+        if rng.gen_range(0.0, 1.0) < chance * delta_time {
+            let speed = rules.difficulty.enemy_speed() * rules.mode.enemy_speed(self.wave) * director.enemy_speed() * rules.mutators.enemy_speed();
+            Enemy::spawn(world, rng, width, director.min_enemy_size(), rules.mutators.enemy_scale(), speed);
         }
     }
 
-    pub fn update(&mut self, delta_time: f32) {
-        self.try_add_enemy();
+    fn spawn_level_enemies(&mut self, world: &mut World, width: f32, rules: RunRules) {
+        let Some(level) = campaign::levels().get(self.wave as usize - 1) else {
+            return;
+        };
+        let speed_factor = rules.difficulty.enemy_speed() * rules.mutators.enemy_speed();
+        let size_factor = rules.mutators.enemy_scale();
 
-        for enemy in self.list.iter_mut() {
-            enemy.update(delta_time);
+        while let Some((time, spawn)) = level.spawns.get(self.next_spawn) {
+            if *time > self.wave_time {
+                break;
+            }
+            self.next_spawn += 1;
+
+            match *spawn {
+                LevelSpawn::Row { count, size, speed } => {
+                    let size = size * size_factor;
+                    let spacing = width / count as f32;
+                    for index in 0..count {
+                        let position = vec2(spacing * (index as f32 + 0.5), -size);
+                        Enemy::spawn_at(world, position, size, speed * speed_factor, 1);
+                    }
+                }
+                LevelSpawn::Boss { size, hit_points, speed } => {
+                    let size = size * size_factor;
+                    let hit_points = if rules.has(Mutator::OneHitKills) { 1 } else { hit_points };
+                    if let Some(entity) = Enemy::spawn_at(world, vec2(width / 2.0, -size), size, speed * speed_factor, hit_points) {
+                        self.bosses.push(Boss {
+                            entity,
+                            hit_points,
+                            max_hit_points: hit_points,
+                            speed: speed * speed_factor,
+                            fire_time: 0.0,
+                        });
+                    }
+                }
+            }
         }
+    }
 
-        self.list.retain(|enemy| enemy.position().y < screen_height() + enemy.size());
+    /// Moves to the next level once the current one has spawned everything
+    /// and its enemies are gone.
+    fn check_level_cleared(&mut self, world: &World) {
+        let Some(level) = campaign::levels().get(self.wave as usize - 1) else {
+            return;
+        };
 
-        self.sprite_small.update();
-        self.sprite_medium.update();
-        self.sprite_big.update();
+        if self.next_spawn == level.spawns.len() && world.occupancy(EntityPool::Enemies).0 == 0 {
+            if self.wave as usize == campaign::levels().len() {
+                self.completed = true;
+            } else {
+                self.wave += 1;
+                self.wave_time = 0.0;
+                self.next_spawn = 0;
+            }
+        }
     }
 
-    pub fn collides_with<T: Collidable, F: FnMut(&Enemy)>(&mut self, other: &T, mut on_collision: F) -> bool {
-        let mut has_collision = false;
+    /// Bosses come down to their height, then sway from side to side and
+    /// fire volleys of three bolts.
+    fn update_bosses(&mut self, world: &mut World, delta_time: f32, width: f32) {
+        self.bosses.retain_mut(|boss| {
+            let Some(health) = world.healths.get(boss.entity) else {
+                return false;
+            };
+            boss.hit_points = health.hit_points;
+
+            let Some(transform) = world.transforms.get(boss.entity).copied() else {
+                return false;
+            };
+            let Some(velocity) = world.velocities.get_mut(boss.entity) else {
+                return false;
+            };
+
+            let half = transform.size / 2.0;
+            if transform.position.y < BOSS_HOLD_HEIGHT {
+                velocity.linear = vec2(0.0, boss.speed);
+                return true;
+            }
+            if velocity.linear.x == 0.0 || (transform.position.x < half && velocity.linear.x < 0.0) || (transform.position.x > width - half && velocity.linear.x > 0.0) {
+                let direction = if transform.position.x < width / 2.0 { 1.0 } else { -1.0 };
+                velocity.linear = vec2(boss.speed * 2.0 * direction, 0.0);
+            }
 
-        for enemy in self.list.iter_mut() {
-            if enemy.collides_with(other) {
-                enemy.shape.collided = true;
-                has_collision = true;
+            boss.fire_time += delta_time;
+            if boss.fire_time >= BOSS_FIRE_INTERVAL {
+                boss.fire_time -= BOSS_FIRE_INTERVAL;
+                self.shooters.extend([-half / 2.0, 0.0, half / 2.0].map(|x| transform.position + vec2(x, half)));
+            }
+
+            true
+        });
+    }
 
-                on_collision(&enemy);
+    /// Each enemy on screen fires at `fire_rate` shots per second on average.
+    fn fire(&mut self, world: &World, rng: &RandGenerator, delta_time: f32, fire_rate: f32) {
+        for (entity, transform) in world.transforms.iter() {
+            if entity.pool() == EntityPool::Enemies && transform.position.y > 0.0 && rng.gen_range(0.0, 1.0) < fire_rate * delta_time {
+                self.shooters.push(transform.position + vec2(0.0, transform.size / 2.0));
             }
         }
+    }
 
-        self.list.retain(|enemy| !enemy.shape.collided);
+    pub fn update(&mut self, world: &mut World, rng: &RandGenerator, delta_time: f32, width: f32, rules: RunRules, director: &Director) {
+        self.wave_time += delta_time;
+        self.shooters.clear();
+
+        if rules.mode.has_levels() {
+            self.spawn_level_enemies(world, width, rules);
+            self.update_bosses(world, delta_time, width);
+            self.check_level_cleared(world);
+        } else {
+            if self.wave_time >= WAVE_DURATION {
+                self.wave_time -= WAVE_DURATION;
+                self.wave += 1;
+            }
+
+            self.try_add_enemy(world, rng, delta_time, width, rules, director);
+        }
 
-        has_collision
+        let fire_rate = rules.difficulty.enemy_fire_rate();
+        if fire_rate > 0.0 {
+            self.fire(world, rng, delta_time, fire_rate);
+        }
+
+        for position in self.shooters.iter() {
+            Bullet::spawn_enemy(world, *position, ENEMY_BULLET_SPEED);
+        }
     }
+}
 
-    pub fn draw(&self, sprites_config: &SpritesConfig) {
-        let small_frame = self.sprite_small.frame();
-        let medium_frame = self.sprite_medium.frame();
-        let big_frame = self.sprite_big.frame();
-
-        for enemy in self.list.iter() {
-            let size = enemy.size();
-            if size < 32. {
-                enemy.draw(&sprites_config.enemy_small_texture, &small_frame);
-            } else if size < 48. {
-                enemy.draw(&sprites_config.enemy_medium_texture, &medium_frame);
-            } else {
-                enemy.draw(&sprites_config.enemy_big_texture, &big_frame);
-            }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Enemies spawned over `seconds` of random waves stepped by `delta_time`.
+    fn spawned(delta_time: f32, seconds: f32) -> usize {
+        let mut world = World::new();
+        let mut enemies = Enemies::new();
+        let rng = RandGenerator::new();
+        rng.srand(7);
+
+        let mut count = 0;
+        for _ in 0..(seconds / delta_time) as usize {
+            enemies.update(&mut world, &rng, delta_time, 800.0, RunRules::default(), &Director::default());
+            count += world.occupancy(EntityPool::Enemies).0;
+            world.clear();
         }
+        count
     }
 
-    pub fn clear(&mut self) {
-        self.list = Vec::new();
+    #[test]
+    fn spawn_rate_does_not_depend_on_the_step() {
+        let slow = spawned(1.0 / 30.0, 300.0) as f32;
+        let fast = spawned(1.0 / 120.0, 300.0) as f32;
+
+        assert!(slow > 100.0);
+        assert!((fast / slow - 1.0).abs() < 0.15, "{} at 30 fps, {} at 120 fps", slow, fast);
     }
-}
\ No newline at end of file
+}
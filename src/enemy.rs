@@ -1,76 +1,88 @@
 use macroquad::{
-    color::WHITE, math::{vec2, Vec2},
-    prelude::animation::AnimationFrame,
-    rand::{self, ChooseRandom},
-    texture::{draw_texture_ex, DrawTextureParams, Texture2D},
-    window::screen_width
+    math::{vec2, Vec2},
+    rand::RandGenerator,
 };
 
-use crate::{collidable::Collidable, constants::COLORS, shape::{Shape, ShapeType}
+use crate::{collision_layers::CollisionLayer, components::{Collider, Health, Sprite, SpriteTexture, Team, Transform, Velocity}, shape::ShapeType, sprites_config::SpritesConfig, world::{Entity, EntityPool, World}
 };
 
-#[derive(Debug, Default)]
-pub struct Enemy {
-    pub shape: Shape,
+#[derive(PartialEq, Eq, Default, Clone, Copy, Debug)]
+pub enum EnemySize {
+    #[default]
+    Small,
+    Medium,
+    Big,
 }
 
-impl Collidable for Enemy {
-    fn shape_type(&self) -> ShapeType {
-        self.shape.shape_type()
-    }
-
-    fn position(&self) -> macroquad::prelude::Vec2 {
-        self.shape.position()
-    }
-
-    fn size(&self) -> f32 {
-        self.shape.size()
+impl EnemySize {
+    pub fn from_size(size: f32) -> Self {
+        if size < 32. {
+            EnemySize::Small
+        } else if size < 48. {
+            EnemySize::Medium
+        } else {
+            EnemySize::Big
+        }
     }
-}
 
-impl Enemy {
-    pub fn new() -> Self {
-        Enemy {
-            shape: Self::create_shape(),
+    pub fn texture(&self) -> SpriteTexture {
+        match self {
+            EnemySize::Small => SpriteTexture::EnemySmall,
+            EnemySize::Medium => SpriteTexture::EnemyMedium,
+            EnemySize::Big => SpriteTexture::EnemyBig,
         }
     }
+}
 
-    pub fn get_speed(&self) -> f32 {
-        self.shape.speed
-    }
+/// Spawns the enemies falling from the top of the screen.
+pub struct Enemy;
 
-    pub fn create_shape() -> Shape {
-        let size = rand::gen_range(16.0, 64.0);
+impl Enemy {
+    /// Spawns above a play area `width` pixels wide, at least `min_size`
+    /// pixels big before `size_factor` scales it, and `speed_factor` scaling
+    /// its speed. Returns `None` when the enemy pool is full.
+    pub fn spawn(world: &mut World, rng: &RandGenerator, width: f32, min_size: f32, size_factor: f32, speed_factor: f32) -> Option<Entity> {
+        let size = rng.gen_range(min_size, 64.0) * size_factor;
         let half = size / 2.0;
+        let position = Vec2 {
+            x: rng.gen_range(half, width - half),
+            y: -size,
+        };
+        let speed = rng.gen_range(50.0, 150.0) * speed_factor;
 
-        Shape {
-            shape_type: ShapeType::Square,
-            size,
-            speed: rand::gen_range(50.0, 150.0),
-            position: Vec2 {
-                x: rand::gen_range(half, screen_width() - half),
-                y: -size,
-            },
-            color: *COLORS.choose().unwrap(),
-            collided: false,
-        }
+        Self::spawn_at(world, position, size, speed, 1)
     }
 
-    pub fn update(&mut self, delta_time: f32) {
-        self.shape.position.y += self.shape.speed * delta_time;
-    }
+    /// Spawns an enemy of `size` pixels at `position`, falling at `speed`
+    /// pixels per second. Returns `None` when the enemy pool is full.
+    pub fn spawn_at(world: &mut World, position: Vec2, size: f32, speed: f32, hit_points: u32) -> Option<Entity> {
+        let enemy_size = EnemySize::from_size(size);
+        let animation = match enemy_size {
+            EnemySize::Small => SpritesConfig::get_enemy_small_sprite(),
+            EnemySize::Medium => SpritesConfig::get_enemy_medium_sprite(),
+            EnemySize::Big => SpritesConfig::get_enemy_big_sprite(),
+        };
+
+        let entity = world.spawn(EntityPool::Enemies)?
+            .with(Transform::new(position, size))
+            .with(Velocity {
+                linear: vec2(0.0, speed),
+                angular: 0.0,
+            })
+            .with(Collider {
+                shape_type: ShapeType::Square,
+                layer: CollisionLayer::Enemy,
+            })
+            .with(Health { hit_points })
+            .with(Team::Enemy)
+            .with(Sprite {
+                texture: enemy_size.texture(),
+                animation,
+                draw_size: vec2(size, size),
+                elapsed: 0.0,
+            })
+            .build();
 
-    pub fn draw(&self, texture: &Texture2D, frame: &AnimationFrame) {
-        draw_texture_ex(
-            texture,
-            self.shape.position.x - self.shape.size / 2.,
-            self.shape.position.y - self.shape.size / 2.,
-            WHITE,
-            DrawTextureParams {
-                dest_size: Some(vec2(self.shape.size, self.shape.size)),
-                source: Some(frame.source_rect),
-                ..Default::default()
-            },
-        );
+        Some(entity)
     }
-}
\ No newline at end of file
+}
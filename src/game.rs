@@ -1,20 +1,54 @@
-use macroquad::{color::*, input::{is_key_down, is_key_pressed, KeyCode}, text::{draw_text, measure_text}, time::get_frame_time, window::{screen_height, screen_width}};
+use std::io;
 
-use crate::{bullet::Bullet, collidable::Collidable, constants::*, enemies::Enemies, game_state::GameState, hero::Hero, menu::Menu, particles::Particles, scores::Scores, shaders::{self, StarfieldShader}, sound_config::{self, SoundConfig}, sprites_config::SpritesConfig};
+use macroquad::{color::{GOLD, GREEN, ORANGE}, shapes::draw_rectangle, input::{is_key_pressed, KeyCode}, math::{vec2, Vec2}, miniquad::date, time::get_frame_time, window::{screen_height, screen_width}};
 
-pub struct Game {
-    pub game_state: GameState,
+use crate::{achievements::{Achievement, Achievements}, constants::*, debug_overlay::DebugOverlay, game_settings::GameSettings, gamepad::Gamepads, hud, input::PlayerInput, leaderboards::{LeaderboardCategory, LeaderboardEntry, Leaderboards}, lifetime_stats::{self, LifetimeStats}, menu::Menu, mutators::Mutator, online_leaderboard::OnlineLeaderboard, particles::Particles, popups::Popups, replay::{Replay, ReplayFrame}, run_rules::RunRules, run_stats::RunStats, save_file::{SaveData, SaveFile}, scores::Scores, shaders::{self, StarfieldShader}, simulation::{Simulation, SimulationEvent}, sound_config::SoundConfig, sprites_config::SpritesConfig, systems, toasts::Toasts};
+
+/// Multiplier from which a new step flashes the screen.
+const BIG_COMBO_MULTIPLIER: u32 = 4;
+/// Seconds the flash of a big combo takes to fade.
+const COMBO_FLASH_DURATION: f32 = 0.3;
 
-    pub lives: u32,
-    pub hero: Hero,
-    pub enemies: Enemies,
-    pub bullets: Vec<Bullet>,
+fn play_area() -> Vec2 {
+    vec2(screen_width(), screen_height())
+}
+
+/// State shared by every scene: the run being played and the loaded assets.
+pub struct Game {
+    /// Mode and difficulty of the next run.
+    pub rules: RunRules,
+    pub simulation: Simulation,
+    pub pixel_perfect_collisions: bool,
 
     pub scores: Scores,
+    pub leaderboards: Leaderboards,
+    pub achievements: Achievements,
+    /// Totals of every finished run.
+    pub lifetime_stats: LifetimeStats,
+    /// Stats of the last finished run of this session.
+    pub last_run: Option<RunStats>,
+    pub online: OnlineLeaderboard,
+    /// Initials entered last this session, the name of runs submitted
+    /// online without entering the local leaderboard.
+    pub initials: Option<String>,
+    save_file: SaveFile,
+    /// Inputs of the current run.
+    pub replay: Replay,
+
+    pub gamepads: Gamepads,
+
+    /// Messages shown above every scene.
+    pub toasts: Toasts,
 
     pub shaders: StarfieldShader,
 
     pub particles: Particles,
+    /// Scores and damage rising from kills and hits.
+    pub popups: Popups,
+    /// Seconds left of the flash of a big combo.
+    combo_flash: f32,
+
+    pub debug_overlay: DebugOverlay,
 
     pub sprites_config: SpritesConfig,
 
@@ -28,165 +62,225 @@ impl Game {
 
         Menu::initialize().await;
 
-        Self {
-            game_state: GameState::MainMenu,
-            lives: INITIAL_LIVES,
-            hero: Hero::new(),
-            enemies: Default::default(),
-            bullets: Default::default(),
-            scores: Scores::new(),
+        let sprites_config = SpritesConfig::new().await;
+        let particles = Particles::new(&sprites_config.explosion_texture);
+
+        let mut save_file = SaveFile::new();
+        let (save_data, warnings) = save_file.load();
+        let mut toasts = Toasts::default();
+        for warning in warnings {
+            toasts.push(warning, ORANGE);
+        }
+
+        let definitions = Achievement::load_all().await.unwrap_or_else(|error| {
+            toasts.push(format!("Could not load the achievements: {}", error), ORANGE);
+            Vec::new()
+        });
+
+        let mut game = Self {
+            rules: Default::default(),
+            simulation: Simulation::new(0, play_area(), Default::default()),
+            pixel_perfect_collisions: PIXEL_PERFECT_COLLISIONS,
+            scores: Scores::new(save_data.high_score),
+            leaderboards: save_data.leaderboards,
+            achievements: Achievements::new(definitions, save_data.achievements),
+            lifetime_stats: save_data.lifetime_stats,
+            last_run: None,
+            online: OnlineLeaderboard::new(),
+            initials: None,
+            save_file,
+            replay: Default::default(),
+            gamepads: Gamepads::new(),
+            toasts,
             shaders: shaders::StarfieldShader::default(),
-            particles: Particles::new(),
-            sprites_config: SpritesConfig::new().await,
+            particles,
+            popups: Popups::new().await,
+            combo_flash: 0.0,
+            debug_overlay: Default::default(),
+            sprites_config,
             sound_config,
-        }
+        };
+        game.apply_settings(save_data.settings);
+
+        game
     }
 
-    fn add_bullet(&mut self) {
-        self.bullets.push(Bullet::new(&self.hero));
+    pub fn settings(&self) -> GameSettings {
+        GameSettings {
+            music_enabled: self.sound_config.music_enabled,
+            effects_enabled: self.sound_config.effects_enabled,
+            pixel_perfect_collisions: self.pixel_perfect_collisions,
+            debug_overlay: self.debug_overlay.visible,
+        }
     }
 
-    pub fn restart(&mut self) {
-        self.lives = INITIAL_LIVES;
-        self.scores.score = 0;
-        self.hero.restart();
-        self.enemies.clear();
-        self.bullets = Vec::new();
-        self.particles.clear();
-        self.game_state = GameState::Playing;
+    pub fn apply_settings(&mut self, settings: GameSettings) {
+        self.sound_config.set_music_enabled(settings.music_enabled);
+        self.sound_config.effects_enabled = settings.effects_enabled;
+        self.pixel_perfect_collisions = settings.pixel_perfect_collisions;
+        self.debug_overlay.visible = settings.debug_overlay;
     }
 
-    fn update_bullets(&mut self, delta_time: f32) {
-        for bullet in self.bullets.iter_mut() {
-            bullet.update(delta_time);
+    /// Writes the high score, leaderboards, settings, achievements and
+    /// stats, a failure only shows a warning.
+    pub fn save(&mut self) {
+        let data = SaveData {
+            high_score: self.scores.high_score,
+            leaderboards: std::mem::take(&mut self.leaderboards),
+            settings: self.settings(),
+            achievements: self.achievements.unlocked().to_vec(),
+            lifetime_stats: self.lifetime_stats,
+        };
+
+        if let Err(error) = self.save_file.save(&data) {
+            self.toasts.push(format!("Could not save: {}", error), ORANGE);
         }
 
-        self.bullets.retain(|bullet| bullet.position().y > 0.0 - bullet.size() / 2.0);
+        self.leaderboards = data.leaderboards;
     }
 
-    fn update_playing(&mut self, delta_time: f32) {
-        self.enemies.update(delta_time);
-        self.update_bullets(delta_time);
+    /// Stores the replay of the current run, returns where it was written.
+    pub fn save_replay(&mut self) -> io::Result<String> {
+        self.save_file.write_file(&self.replay.file_name(), &self.replay.to_text())
     }
 
-    fn check_playing_inputs(&mut self, delta_time: f32) {
-        self.hero.check_inputs(delta_time, &mut self.shaders);
+    /// Stores the stats of the last run and of every run as CSV, returns
+    /// where they were written.
+    pub fn export_stats(&mut self) -> io::Result<String> {
+        let csv = lifetime_stats::stats_csv(self.last_run.as_ref(), &self.lifetime_stats);
+        self.save_file.write_file("stats.csv", &csv)
+    }
 
-        if is_key_pressed(KeyCode::Space) {
-            self.add_bullet();
-            self.sound_config.play_sound_laser();
+    /// Adds the current run to the lifetime stats, if it was played at all.
+    pub fn end_run(&mut self) {
+        let stats = self.simulation.stats;
+        if stats.time_survived == 0.0 {
+            return;
         }
 
-        if is_key_down(KeyCode::Escape) {
-            self.game_state = GameState::Paused;
+        // Practice runs don't count in the stats nor unlock practice starts
+        if self.simulation.rules.is_ranked() {
+            self.lifetime_stats.add_run(self.simulation.score, &stats);
+            self.lifetime_stats.reached(self.simulation.rules.mode, self.simulation.enemies.wave);
         }
+        self.last_run = Some(stats);
+        self.save();
     }
 
-    fn check_hero_collisions(&mut self) -> bool {
-        self.enemies.collides_with(&self.hero, |enemy| {
-            self.scores.score += enemy.size().round() as u32;
-            self.particles.create_explosion(enemy.position().x, enemy.position().y, enemy.size(), &self.sprites_config.explosion_texture);
-            self.sound_config.play_sound_explosion();
-        })
-    }
-
-    fn check_bullets_collisions(&mut self) {
-        for bullet in self.bullets.iter_mut() {
-            if self.enemies.collides_with(bullet, |enemy| {
-                self.scores.score += enemy.size().round() as u32;
-                self.particles.create_explosion(enemy.position().x, enemy.position().y, enemy.size(), &self.sprites_config.explosion_texture);
-                self.sound_config.play_sound_explosion();
-            }) {
-                bullet.set_collided(true);
-            };
-        }
+    pub fn restart(&mut self) {
+        let seed = (date::now() * 1000.0) as u64;
+        let area = play_area();
 
-        self.bullets.retain(|bullet| !bullet.get_collided());
-        self.particles.clean();
+        self.simulation.restart(seed, area, self.rules);
+        self.replay = Replay::new(seed, area, self.pixel_perfect_collisions, self.rules);
+        self.particles.clear();
+        self.popups.clear();
+        self.combo_flash = 0.0;
     }
 
-    fn check_collisions(&mut self) {
-        if self.check_hero_collisions() {
-            self.lives = self.lives.saturating_sub(1);
-
-            if self.lives < 1
-            {
-                self.scores.check_score_vs_high_score();
-                self.game_state = GameState::GameOver;
-            }
+    pub fn leaderboard_category(&self) -> LeaderboardCategory {
+        LeaderboardCategory {
+            mode: self.simulation.rules.mode,
+            difficulty: self.simulation.rules.difficulty,
         }
-
-        self.check_bullets_collisions();
     }
 
-    fn draw_bullets(&self) {
-        for bullet in self.bullets.iter() {
-            bullet.draw(&self.sprites_config);
+    /// Leaderboard entry for the current run, without a name.
+    pub fn leaderboard_entry(&self) -> LeaderboardEntry {
+        LeaderboardEntry {
+            name: String::new(),
+            score: self.simulation.score,
+            wave: self.simulation.enemies.wave,
+            duration: self.simulation.stats.time_survived,
+            timestamp: date::now() as u64,
+            seed: self.replay.seed,
+            mutators: self.simulation.rules.mutators,
         }
     }
 
-    fn draw_playing(&mut self) {
-        self.shaders.draw();
-
-        self.hero.draw(&self.sprites_config);
-        self.draw_bullets();
-        self.enemies.draw(&self.sprites_config);
-        self.particles.draw();
+    pub fn is_game_over(&self) -> bool {
+        self.simulation.is_over()
     }
 
-    pub fn playing(&mut self) {
+    /// Advances the run by one frame.
+    pub fn update(&mut self) {
         let delta_time = get_frame_time();
+        let input = PlayerInput::read(&self.gamepads);
+        let area = play_area();
+
+        let masks = self.pixel_perfect_collisions.then_some(&self.sprites_config.masks);
+        for event in self.simulation.step(delta_time, input, area, masks) {
+            match *event {
+                SimulationEvent::ShotFired => self.sound_config.play_sound_laser(),
+                SimulationEvent::EnemyDestroyed { position, size, points, multiplier } => {
+                    let size = size * (1.0 + 0.25 * (multiplier - 1) as f32);
+                    self.particles.create_explosion(position.x, position.y, size);
+                    self.popups.score(position, points, multiplier);
+                    self.sound_config.play_sound_explosion();
+                }
+                SimulationEvent::EnemyDamaged { position, damage } => self.popups.damage(position, damage),
+                SimulationEvent::HeroHit { position } => self.popups.damage(position, 1),
+                SimulationEvent::MultiplierUp { multiplier } => {
+                    self.sound_config.play_sound_combo(multiplier);
+                    if multiplier >= BIG_COMBO_MULTIPLIER {
+                        self.combo_flash = COMBO_FLASH_DURATION;
+                    }
+                }
+                SimulationEvent::LevelCleared { level, bonus } => {
+                    self.toasts.push(format!("Level {} cleared! +{}", level, bonus), GREEN);
+                }
+                SimulationEvent::ComboBroken { .. } => {}
+            }
+        }
 
-        self.update_playing(delta_time);
-
-        self.check_playing_inputs(delta_time);
-
-        self.check_collisions();
+        let frame = ReplayFrame {
+            delta_time,
+            input,
+            area,
+            pixel_perfect_collisions: self.pixel_perfect_collisions,
+        };
+        self.replay.record(frame, self.simulation.checkpoint());
+
+        let unlocked = if self.simulation.rules.is_ranked() { self.achievements.check(&self.simulation) } else { Vec::new() };
+        for name in unlocked.iter() {
+            self.toasts.push(format!("Achievement unlocked: {}", name), GOLD);
+        }
+        if !unlocked.is_empty() {
+            self.save();
+        }
 
-        self.draw_playing();
+        if input.contains(PlayerInput::RIGHT) {
+            self.shaders.inc_by(0.05 * delta_time);
+        }
+        if input.contains(PlayerInput::LEFT) {
+            self.shaders.dec_by(0.05 * delta_time);
+        }
+        self.particles.update(delta_time);
+        self.popups.update(delta_time);
+        self.combo_flash = (self.combo_flash - delta_time).max(0.0);
 
+        if is_key_pressed(KeyCode::F3) {
+            self.debug_overlay.toggle();
+        }
     }
 
-    pub fn main_menu(&mut self) {
-        Menu::main_menu(
-            || std::process::exit(0),
-            || self.restart()
-        );
-        // if is_key_pressed(KeyCode::Escape) {
-        //     std::process::exit(0);
-        // }
-        // if is_key_pressed(KeyCode::Enter) {
-        //     self.restart();
-        // }
-
-        // let text = "Press ENTER";
-        // let text_dimensions = measure_text(text, None, 50, 1.0);
-        // draw_text(
-        //     text,
-        //     screen_width() / 2.0 - text_dimensions.width / 2.0,
-        //     screen_height() / 2.0,
-        //     50.0,
-        //     WHITE,
-        // );
+    pub fn draw(&mut self) {
+        self.shaders.draw();
 
-    }
+        systems::render(&self.simulation.world, &self.sprites_config);
+        self.particles.draw();
+        self.popups.draw();
 
-    pub fn paused(&mut self) {
-        if is_key_pressed(KeyCode::Space) {
-            self.game_state = GameState::Playing;
+        if self.combo_flash > 0.0 {
+            let mut color = hud::multiplier_color(self.simulation.combo.multiplier());
+            color.a = 0.3 * self.combo_flash / COMBO_FLASH_DURATION;
+            draw_rectangle(0.0, 0.0, screen_width(), screen_height(), color);
         }
-        if is_key_pressed(KeyCode::Escape) {
-            std::process::exit(0);
+
+        if !self.simulation.rules.has(Mutator::NoHud) {
+            hud::draw_hud(&self.simulation, &self.scores);
         }
 
-        let text = "Paused";
-        let text_dimensions = measure_text(text, None, 50, 1.0);
-        draw_text(
-            text,
-            screen_width() / 2.0 - text_dimensions.width / 2.0,
-            screen_height() / 2.0,
-            50.0,
-            WHITE,
-        );
+        self.debug_overlay.draw(&self.simulation.world, &self.simulation.director, &self.particles);
     }
 }
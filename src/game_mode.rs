@@ -0,0 +1,93 @@
+use crate::difficulty::Difficulty;
+
+/// Seconds of a Time Attack run.
+pub const TIME_ATTACK_DURATION: f32 = 120.0;
+/// Points for clearing a campaign level, times its number.
+pub const LEVEL_BONUS: u32 = 500;
+
+/// Rules of a run: how it ends, where enemies come from and how kills score.
+#[derive(PartialEq, Eq, Hash, Default, Clone, Copy, Debug)]
+pub enum GameMode {
+    /// Enemies keep coming until the ship is destroyed.
+    #[default]
+    Endless,
+    /// Scripted levels from `assets/campaign.txt`, won by clearing the last.
+    Campaign,
+    /// As many points as possible before the time runs out.
+    TimeAttack,
+    /// A single life against waves getting faster and denser.
+    Survival,
+}
+
+impl GameMode {
+    pub const ALL: [GameMode; 4] = [GameMode::Endless, GameMode::Campaign, GameMode::TimeAttack, GameMode::Survival];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameMode::Endless => "Endless",
+            GameMode::Campaign => "Campaign",
+            GameMode::TimeAttack => "Time Attack",
+            GameMode::Survival => "Survival",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.name() == name)
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            GameMode::Endless => "Survive as long as you can",
+            GameMode::Campaign => "Clear every level and defeat the bosses",
+            GameMode::TimeAttack => "Score as much as you can in 2 minutes",
+            GameMode::Survival => "One life, each wave faster than the last",
+        }
+    }
+
+    pub fn starting_lives(&self, difficulty: Difficulty) -> u32 {
+        match self {
+            GameMode::Survival => 1,
+            _ => difficulty.starting_lives(),
+        }
+    }
+
+    /// Seconds after which the run ends, if any.
+    pub fn time_limit(&self) -> Option<f32> {
+        match self {
+            GameMode::TimeAttack => Some(TIME_ATTACK_DURATION),
+            _ => None,
+        }
+    }
+
+    /// Whether enemies come from the campaign levels instead of random
+    /// spawns.
+    pub fn has_levels(&self) -> bool {
+        *self == GameMode::Campaign
+    }
+
+    /// Added to the spawn threshold in `wave`, Survival spawning more as
+    /// waves go by.
+    pub fn spawn_threshold_offset(&self, wave: u32) -> i32 {
+        match self {
+            GameMode::Survival => -(wave.saturating_sub(1).min(8) as i32),
+            _ => 0,
+        }
+    }
+
+    /// Factor of the speed of the enemies spawned in `wave`.
+    pub fn enemy_speed(&self, wave: u32) -> f32 {
+        match self {
+            GameMode::Survival => (1.0 + 0.1 * wave.saturating_sub(1) as f32).min(2.0),
+            _ => 1.0,
+        }
+    }
+
+    /// Points of a kill worth `points` in `wave`, Survival rewarding the
+    /// later waves.
+    pub fn kill_points(&self, points: u32, wave: u32) -> u32 {
+        match self {
+            GameMode::Survival => points * wave,
+            _ => points,
+        }
+    }
+}
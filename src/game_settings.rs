@@ -0,0 +1,61 @@
+/// Options of the settings screen, kept between sessions.
+#[derive(Clone, Copy, Debug)]
+pub struct GameSettings {
+    pub music_enabled: bool,
+    pub effects_enabled: bool,
+    pub pixel_perfect_collisions: bool,
+    pub debug_overlay: bool,
+}
+
+impl Default for GameSettings {
+    fn default() -> Self {
+        GameSettings {
+            music_enabled: true,
+            effects_enabled: true,
+            pixel_perfect_collisions: crate::constants::PIXEL_PERFECT_COLLISIONS,
+            debug_overlay: false,
+        }
+    }
+}
+
+impl GameSettings {
+    /// One "name on|off" line per option.
+    pub fn to_text(&self) -> String {
+        self.options()
+            .iter()
+            .map(|(name, enabled)| format!("{} {}\n", name, if *enabled { "on" } else { "off" }))
+            .collect()
+    }
+
+    /// Reads the options written by `to_text`, missing ones keep their
+    /// default.
+    pub fn from_text(text: &str) -> Self {
+        let mut settings = Self::default();
+
+        for line in text.lines() {
+            let Some((name, value)) = line.split_once(' ') else {
+                continue;
+            };
+            let enabled = value == "on";
+
+            match name {
+                "music" => settings.music_enabled = enabled,
+                "effects" => settings.effects_enabled = enabled,
+                "pixel_perfect_collisions" => settings.pixel_perfect_collisions = enabled,
+                "debug_overlay" => settings.debug_overlay = enabled,
+                _ => {}
+            }
+        }
+
+        settings
+    }
+
+    fn options(&self) -> [(&'static str, bool); 4] {
+        [
+            ("music", self.music_enabled),
+            ("effects", self.effects_enabled),
+            ("pixel_perfect_collisions", self.pixel_perfect_collisions),
+            ("debug_overlay", self.debug_overlay),
+        ]
+    }
+}
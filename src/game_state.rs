@@ -1,6 +0,0 @@
-pub enum GameState {
-    MainMenu,
-    Playing,
-    Paused,
-    GameOver,
-}
\ No newline at end of file
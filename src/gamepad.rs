@@ -0,0 +1,141 @@
+use macroquad::math::Vec2;
+#[cfg(not(target_arch = "wasm32"))]
+use macroquad::math::vec2;
+
+/// Stick axes closer to the center than this are ignored.
+#[cfg(not(target_arch = "wasm32"))]
+const STICK_DEAD_ZONE: f32 = 0.3;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum GamepadButton {
+    /// A on Xbox pads, cross on PlayStation ones.
+    South,
+    East,
+    Start,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl GamepadButton {
+    fn to_gilrs(self) -> gilrs::Button {
+        match self {
+            GamepadButton::South => gilrs::Button::South,
+            GamepadButton::East => gilrs::Button::East,
+            GamepadButton::Start => gilrs::Button::Start,
+            GamepadButton::DPadUp => gilrs::Button::DPadUp,
+            GamepadButton::DPadDown => gilrs::Button::DPadDown,
+            GamepadButton::DPadLeft => gilrs::Button::DPadLeft,
+            GamepadButton::DPadRight => gilrs::Button::DPadRight,
+        }
+    }
+}
+
+/// Every connected gamepad, read as one.
+///
+/// Gamepads are only supported on native builds, on the web no button is
+/// ever pressed.
+#[derive(Default)]
+pub struct Gamepads {
+    #[cfg(not(target_arch = "wasm32"))]
+    gilrs: Option<gilrs::Gilrs>,
+    /// Buttons pressed since the last `update`.
+    pressed: Vec<GamepadButton>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Gamepads {
+    pub fn new() -> Self {
+        Gamepads {
+            // Without a gamepad backend the game is still playable on keyboard
+            gilrs: gilrs::Gilrs::new().ok(),
+            pressed: Vec::new(),
+        }
+    }
+
+    /// Reads the events of the frame, to be called once per frame.
+    pub fn update(&mut self) {
+        self.pressed.clear();
+
+        let Some(gilrs) = self.gilrs.as_mut() else {
+            return;
+        };
+
+        while let Some(event) = gilrs.next_event() {
+            if let gilrs::EventType::ButtonPressed(button, _) = event.event {
+                self.pressed.extend(Self::from_gilrs(button));
+            }
+        }
+    }
+
+    fn from_gilrs(button: gilrs::Button) -> Option<GamepadButton> {
+        let button = match button {
+            gilrs::Button::South => GamepadButton::South,
+            gilrs::Button::East => GamepadButton::East,
+            gilrs::Button::Start => GamepadButton::Start,
+            gilrs::Button::DPadUp => GamepadButton::DPadUp,
+            gilrs::Button::DPadDown => GamepadButton::DPadDown,
+            gilrs::Button::DPadLeft => GamepadButton::DPadLeft,
+            gilrs::Button::DPadRight => GamepadButton::DPadRight,
+            _ => return None,
+        };
+
+        Some(button)
+    }
+
+    pub fn is_down(&self, button: GamepadButton) -> bool {
+        self.gilrs.as_ref().is_some_and(|gilrs| {
+            gilrs.gamepads().any(|(_, gamepad)| gamepad.is_pressed(button.to_gilrs()))
+        })
+    }
+
+    /// Position of the first left stick out of its dead zone, y pointing down.
+    ///
+    /// Each axis has its own dead zone, so a stick pushed sideways doesn't
+    /// also move the ship vertically.
+    pub fn left_stick(&self) -> Vec2 {
+        let Some(gilrs) = self.gilrs.as_ref() else {
+            return Vec2::ZERO;
+        };
+
+        gilrs
+            .gamepads()
+            .map(|(_, gamepad)| vec2(
+                dead_zone(gamepad.value(gilrs::Axis::LeftStickX)),
+                dead_zone(-gamepad.value(gilrs::Axis::LeftStickY)),
+            ))
+            .find(|stick| *stick != Vec2::ZERO)
+            .unwrap_or(Vec2::ZERO)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn dead_zone(value: f32) -> f32 {
+    if value.abs() > STICK_DEAD_ZONE { value } else { 0.0 }
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Gamepads {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn update(&mut self) {}
+
+    pub fn is_down(&self, _button: GamepadButton) -> bool {
+        false
+    }
+
+    pub fn left_stick(&self) -> Vec2 {
+        Vec2::ZERO
+    }
+}
+
+impl Gamepads {
+    /// Whether `button` was pressed this frame.
+    pub fn is_pressed(&self, button: GamepadButton) -> bool {
+        self.pressed.contains(&button)
+    }
+}
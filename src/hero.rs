@@ -1,114 +1,122 @@
-use macroquad::{
-    color::{WHITE, YELLOW},
-    input::{is_key_down, KeyCode},
-    math::{clamp, Vec2},
-    prelude::animation::AnimatedSprite,
-    texture::{draw_texture_ex, DrawTextureParams},
-    window::{screen_height, screen_width}
-};
+use macroquad::math::{clamp, vec2, Vec2};
 
 use crate::{
-    sprites_config::SpritesConfig, collidable::Collidable, constants::MOVEMENT_SPEED, shaders::StarfieldShader, shape::{Shape, ShapeType}
+    sprites_config::SpritesConfig, collision_layers::CollisionLayer, components::{Collider, Sprite, SpriteTexture, Team, Transform, Velocity}, constants::MOVEMENT_SPEED, input::PlayerInput, shape::ShapeType, world::{Entity, EntityPool, World}
 };
 
+/// Controls the player's ship entity.
 pub struct Hero {
-    shape: Shape,
-    sprite: AnimatedSprite,
-}
-
-impl Default for Hero {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-impl Collidable for Hero {
-    fn shape_type(&self) -> ShapeType {
-        self.shape.shape_type()
-    }
-
-    fn position(&self) -> Vec2 {
-        self.shape.position()
-    }
-
-    fn size(&self) -> f32 {
-        self.shape.size()
-    }
+    pub entity: Entity,
+    speed: f32,
 }
 
 impl Hero {
 
-    pub fn new() -> Hero {
+    /// Spawns the ship in the middle of a play area of `area` pixels, at
+    /// `scale` times its usual size.
+    pub fn new(world: &mut World, area: Vec2, scale: f32) -> Hero {
         Hero {
-            shape: Self::create_shape(),
-            sprite: SpritesConfig::get_ship_sprite(),
+            entity: Self::spawn(world, area, scale),
+            speed: MOVEMENT_SPEED,
         }
     }
 
-    fn create_shape() -> Shape {
-        Shape {
-            shape_type: ShapeType::Circle,
-            size: 32.0,
-            speed: MOVEMENT_SPEED,
-            position: Vec2 {
-                x: screen_width() / 2.0,
-                y: screen_height() / 2.0,
-            },
-            color: YELLOW,
-            collided: false,
-        }
+    fn spawn(world: &mut World, area: Vec2, scale: f32) -> Entity {
+        world.spawn(EntityPool::Hero)
+            .expect("the hero is spawned in an empty world")
+            .with(Transform::new(area / 2.0, 32.0 * scale))
+            .with(Velocity::default())
+            .with(Collider {
+                shape_type: ShapeType::Circle,
+                layer: CollisionLayer::Hero,
+            })
+            .with(Team::Hero)
+            // The ship is drawn at twice its frame size, taller than its
+            // collision box. Only the part of its mask inside the box collides.
+            .with(Sprite {
+                texture: SpriteTexture::Ship,
+                animation: SpritesConfig::get_ship_sprite(),
+                draw_size: vec2(32.0, 48.0) * scale,
+                elapsed: 0.0,
+            })
+            .build()
     }
 
     pub fn get_speed(&self) -> f32 {
-        self.shape.speed
+        self.speed
+    }
+
+    pub fn position(&self, world: &World) -> Vec2 {
+        world.transforms.get(self.entity).map_or(Vec2::ZERO, |transform| transform.position)
     }
 
-    pub fn restart(&mut self) {
-        self.shape = Self::create_shape();
+    /// Spawns the ship again, the world is expected to have been cleared.
+    pub fn restart(&mut self, world: &mut World, area: Vec2, scale: f32) {
+        self.entity = Self::spawn(world, area, scale);
     }
 
-    pub fn check_inputs(&mut self, delta_time: f32, shader: &mut StarfieldShader) {
-        let speed = self.shape.speed * delta_time;
-        let radius = self.shape.size / 2.0;
+    pub fn check_inputs(&mut self, world: &mut World, input: PlayerInput) {
+        let mut direction = Vec2::ZERO;
+        let mut animation = 0;
 
-        self.sprite.set_animation(0);
-        if is_key_down(KeyCode::Right) {
-            self.shape.position.x += speed;
-            shader.inc_by(0.05 * delta_time);
-            self.sprite.set_animation(2);
+        if input.contains(PlayerInput::RIGHT) {
+            direction.x += 1.0;
+            animation = 2;
         }
-        if is_key_down(KeyCode::Left) {
-            self.shape.position.x -= speed;
-            shader.dec_by(0.05 * delta_time);
-            self.sprite.set_animation(1);
+        if input.contains(PlayerInput::LEFT) {
+            direction.x -= 1.0;
+            animation = 1;
         }
-        if is_key_down(KeyCode::Down) {
-            self.shape.position.y += speed;
+        if input.contains(PlayerInput::DOWN) {
+            direction.y += 1.0;
         }
-        if is_key_down(KeyCode::Up) {
-            self.shape.position.y -= speed;
+        if input.contains(PlayerInput::UP) {
+            direction.y -= 1.0;
         }
 
-        self.sprite.update();
+        if let Some(velocity) = world.velocities.get_mut(self.entity) {
+            velocity.linear = direction * self.speed;
+        }
+
+        if let Some(sprite) = world.sprites.get_mut(self.entity) {
+            sprite.animation.set_animation(animation);
+        }
+    }
 
-        self.shape.position.x = clamp(self.shape.position.x, radius, screen_width() - radius);
-        self.shape.position.y = clamp(self.shape.position.y, radius, screen_height() - radius);
+    /// Keeps the ship inside the play area once it has moved.
+    pub fn keep_on_screen(&self, world: &mut World, area: Vec2) {
+        if let Some(transform) = world.transforms.get_mut(self.entity) {
+            let radius = transform.size / 2.0;
 
+            transform.position.x = clamp(transform.position.x, radius, area.x - radius);
+            transform.position.y = clamp(transform.position.y, radius, area.y - radius);
+        }
     }
 
-    pub fn draw(&self, sprites_config: &SpritesConfig) {
-        let ship_frame = self.sprite.frame();
-        draw_texture_ex(
-            &sprites_config.ship_texture,
-            self.shape.position.x - ship_frame.dest_size.x,
-            self.shape.position.y - ship_frame.dest_size.y,
-            WHITE,
-            DrawTextureParams {
-                dest_size: Some(ship_frame.dest_size * 2.0),
-                source: Some(ship_frame.source_rect),
-                ..Default::default()
-            },
-        );
+    /// Moves the ship to the opposite edge once its center leaves the play
+    /// area, instead of keeping it on screen.
+    pub fn wrap_around(&self, world: &mut World, area: Vec2) {
+        if let Some(transform) = world.transforms.get_mut(self.entity) {
+            let position = transform.position;
+            transform.position.x = wrap(position.x, area.x);
+            transform.position.y = wrap(position.y, area.y);
+
+            // The jump shouldn't sweep the collision test across the screen
+            if transform.position != position {
+                transform.previous_position = transform.position;
+            }
+        }
     }
 
-}
\ No newline at end of file
+}
+
+/// Brings `value` back into `0..=length` from the opposite side.
+fn wrap(value: f32, length: f32) -> f32 {
+    if value < 0.0 {
+        value + length
+    } else if value > length {
+        value - length
+    } else {
+        value
+    }
+}
@@ -0,0 +1,271 @@
+use std::{io::{self, BufRead, BufReader, Read, Write}, net::{TcpStream, ToSocketAddrs}, time::Duration};
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+/// Largest body accepted, replays of long runs take a few hundred kilobytes.
+const MAX_BODY_LENGTH: usize = 8 * 1024 * 1024;
+
+/// A request received by a server.
+pub struct Request {
+    pub method: String,
+    /// Path with its query string.
+    pub path: String,
+    pub body: String,
+}
+
+impl Request {
+    /// Path without the query string.
+    pub fn route(&self) -> &str {
+        self.path.split_once('?').map_or(&self.path, |(route, _)| route)
+    }
+
+    /// Value of a query string parameter, percent decoded.
+    pub fn query(&self, name: &str) -> Option<String> {
+        let (_, query) = self.path.split_once('?')?;
+        query
+            .split('&')
+            .filter_map(|parameter| parameter.split_once('='))
+            .find(|(key, _)| *key == name)
+            .and_then(|(_, value)| decode_query_value(value))
+    }
+}
+
+/// Percent encodes a query string value, as mode names have spaces.
+pub fn encode_query_value(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (byte as char).to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+/// Reverses `encode_query_value`, also reading `+` as a space.
+fn decode_query_value(value: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut chars = value.bytes();
+
+    while let Some(byte) = chars.next() {
+        match byte {
+            b'%' => {
+                let hex = [chars.next()?, chars.next()?];
+                // `from_str_radix` would also take a sign
+                if !hex.iter().all(u8::is_ascii_hexdigit) {
+                    return None;
+                }
+                bytes.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+            }
+            b'+' => bytes.push(b' '),
+            _ => bytes.push(byte),
+        }
+    }
+
+    String::from_utf8(bytes).ok()
+}
+
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+/// Reads the header lines up to the blank line, returns the first line and
+/// the body length.
+fn read_head(reader: &mut impl BufRead) -> io::Result<(String, usize)> {
+    let mut first_line = String::new();
+    reader.read_line(&mut first_line)?;
+
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+            break;
+        }
+
+        if let Some((name, value)) = line.split_once(':')
+            && name.trim().eq_ignore_ascii_case("content-length")
+        {
+            content_length = value.trim().parse().map_err(|_| invalid_data("invalid content length"))?;
+        }
+    }
+
+    if content_length > MAX_BODY_LENGTH {
+        return Err(invalid_data("body too large"));
+    }
+
+    Ok((first_line.trim_end().to_string(), content_length))
+}
+
+fn read_body(reader: &mut impl Read, length: usize) -> io::Result<String> {
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    String::from_utf8(body).map_err(|_| invalid_data("body is not UTF-8"))
+}
+
+/// Sends a request and waits for the response, `url` being like
+/// "http://127.0.0.1:7878/scores".
+///
+/// This is minimal HTTP/1.1 for the online leaderboard and its local server:
+/// plain TCP only, one request per connection and `Content-Length` bodies.
+pub fn send(method: &str, url: &str, body: Option<&str>) -> io::Result<Response> {
+    let address = url.strip_prefix("http://").ok_or_else(|| invalid_data("only http:// URLs are supported"))?;
+    let (host, path) = address.split_once('/').map_or((address, "/".to_string()), |(host, path)| (host, format!("/{}", path)));
+
+    let socket_address = host
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| invalid_data("unknown host"))?;
+    let mut stream = TcpStream::connect_timeout(&socket_address, TIMEOUT)?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+
+    let body = body.unwrap_or("");
+    write!(
+        stream,
+        "{} {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+        method,
+        path,
+        host,
+        body.len(),
+        body,
+    )?;
+    stream.flush()?;
+
+    let mut reader = BufReader::new(stream);
+    let (status_line, content_length) = read_head(&mut reader)?;
+    let status = status_line
+        .split(' ')
+        .nth(1)
+        .and_then(|status| status.parse().ok())
+        .ok_or_else(|| invalid_data("invalid status line"))?;
+
+    Ok(Response {
+        status,
+        body: read_body(&mut reader, content_length)?,
+    })
+}
+
+/// Reads a request sent to a server.
+pub fn read_request(stream: &mut TcpStream) -> io::Result<Request> {
+    stream.set_read_timeout(Some(TIMEOUT))?;
+
+    let mut reader = BufReader::new(stream);
+    let (request_line, content_length) = read_head(&mut reader)?;
+
+    let mut parts = request_line.split(' ');
+    let (Some(method), Some(path)) = (parts.next(), parts.next()) else {
+        return Err(invalid_data("invalid request line"));
+    };
+
+    Ok(Request {
+        method: method.to_string(),
+        path: path.to_string(),
+        body: read_body(&mut reader, content_length)?,
+    })
+}
+
+pub fn write_response(stream: &mut TcpStream, response: &Response) -> io::Result<()> {
+    let reason = match response.status {
+        200 => "OK",
+        201 => "Created",
+        400 => "Bad Request",
+        404 => "Not Found",
+        409 => "Conflict",
+        _ => "Unknown",
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nConnection: close\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+        response.status,
+        reason,
+        response.body.len(),
+        response.body,
+    )?;
+    stream.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(path: &str) -> Request {
+        Request { method: "GET".to_string(), path: path.to_string(), body: String::new() }
+    }
+
+    #[test]
+    fn route_drops_the_query_string() {
+        assert_eq!(request("/scores?mode=Endless").route(), "/scores");
+        assert_eq!(request("/scores").route(), "/scores");
+    }
+
+    #[test]
+    fn query_finds_parameters() {
+        let scores = request("/scores?mode=Time%20Attack&difficulty=Normal&flag&limit=5");
+
+        assert_eq!(scores.query("mode").as_deref(), Some("Time Attack"));
+        assert_eq!(scores.query("difficulty").as_deref(), Some("Normal"));
+        assert_eq!(scores.query("limit").as_deref(), Some("5"));
+        assert_eq!(scores.query("flag"), None);
+        assert_eq!(scores.query("seed"), None);
+        assert_eq!(request("/scores").query("mode"), None);
+    }
+
+    #[test]
+    fn query_values_round_trip() {
+        for value in ["Time Attack", "a&b=c?d", "100%", "é🚀", "-_.~"] {
+            let encoded = encode_query_value(value);
+
+            assert!(encoded.bytes().all(|byte| byte.is_ascii_alphanumeric() || b"%-_.~".contains(&byte)));
+            assert_eq!(decode_query_value(&encoded).as_deref(), Some(value));
+        }
+        assert_eq!(encode_query_value("Time Attack"), "Time%20Attack");
+    }
+
+    #[test]
+    fn decodes_percent_escapes_and_plus() {
+        assert_eq!(decode_query_value("Time+Attack").as_deref(), Some("Time Attack"));
+        assert_eq!(decode_query_value("%c3%A9").as_deref(), Some("é"));
+        assert_eq!(decode_query_value("").as_deref(), Some(""));
+    }
+
+    #[test]
+    fn rejects_malformed_percent_escapes() {
+        for value in ["%", "%2", "%zz", "%+1", "%ff"] {
+            assert_eq!(decode_query_value(value), None, "{}", value);
+        }
+    }
+
+    #[test]
+    fn reads_the_head_and_body() {
+        let text = "POST /scores HTTP/1.1\r\nHost: localhost\r\ncontent-length: 4\r\n\r\n{}\r\nextra";
+        let mut reader = text.as_bytes();
+
+        let (first_line, length) = read_head(&mut reader).unwrap();
+        assert_eq!(first_line, "POST /scores HTTP/1.1");
+        assert_eq!(length, 4);
+        assert_eq!(read_body(&mut reader, length).unwrap(), "{}\r\n");
+    }
+
+    #[test]
+    fn head_without_length_has_no_body() {
+        let mut reader = "GET /scores HTTP/1.1\r\nHost: localhost\r\n\r\n".as_bytes();
+
+        assert_eq!(read_head(&mut reader).unwrap().1, 0);
+    }
+
+    #[test]
+    fn rejects_malformed_heads_and_bodies() {
+        let mut invalid_length = "POST / HTTP/1.1\r\nContent-Length: four\r\n\r\n".as_bytes();
+        assert!(read_head(&mut invalid_length).is_err());
+
+        let head = format!("POST / HTTP/1.1\r\nContent-Length: {}\r\n\r\n", MAX_BODY_LENGTH + 1);
+        assert!(read_head(&mut head.as_bytes()).is_err());
+
+        assert!(read_body(&mut "short".as_bytes(), 10).is_err());
+        assert!(read_body(&mut [0xff, 0xfe].as_slice(), 2).is_err());
+    }
+}
@@ -0,0 +1,101 @@
+use macroquad::{color::{Color, GOLD, GRAY, GREEN, MAGENTA, ORANGE, RED, SKYBLUE, WHITE}, shapes::{draw_rectangle, draw_rectangle_lines}, text::{draw_text, measure_text}, window::screen_width};
+
+use crate::{campaign, combo::Combo, scores::Scores, simulation::Simulation};
+
+/// Colour of a score multiplier, warmer as it grows.
+pub fn multiplier_color(multiplier: u32) -> Color {
+    match multiplier {
+        0 | 1 => WHITE,
+        2 => GREEN,
+        3 => SKYBLUE,
+        4 => GOLD,
+        5 | 6 => ORANGE,
+        _ => MAGENTA,
+    }
+}
+
+/// Multiplier and chain of the combo, above a bar of the time left to keep
+/// it going.
+fn draw_combo_meter(combo: &Combo) {
+    const LEFT: f32 = 30.0;
+    const TOP: f32 = 50.0;
+    const WIDTH: f32 = 160.0;
+    const HEIGHT: f32 = 8.0;
+
+    if combo.chain == 0 {
+        return;
+    }
+
+    let color = multiplier_color(combo.multiplier());
+    draw_text(&format!("x{}  chain {}", combo.multiplier(), combo.chain), LEFT, TOP + 12.0, 22.0, color);
+    draw_rectangle(LEFT, TOP + 20.0, WIDTH * combo.timer(), HEIGHT, color);
+    draw_rectangle_lines(LEFT, TOP + 20.0, WIDTH, HEIGHT, 1.0, GRAY);
+}
+
+/// Progress of the run in its mode, centered under the top line, and the
+/// health of the bosses on screen.
+fn draw_mode_status(simulation: &Simulation) {
+    const TOP: f32 = 65.0;
+    const BOSS_BAR_WIDTH: f32 = 300.0;
+
+    let wave = simulation.enemies.wave;
+    let status = if let Some(time_left) = simulation.time_left() {
+        let seconds = time_left.ceil() as u32;
+        format!("Time: {}:{:02}", seconds / 60, seconds % 60)
+    } else if simulation.rules.mode.has_levels() {
+        let name = campaign::levels().get(wave as usize - 1).map_or("", |level| level.name.as_str());
+        format!("Level {}: {}", wave, name)
+    } else {
+        format!("Wave {}", wave)
+    };
+    let status = if simulation.rules.practice.is_some() { format!("{} (Practice)", status) } else { status };
+
+    let text_dimensions = measure_text(&status, None, 22, 1.0);
+    draw_text(&status, screen_width() / 2.0 - text_dimensions.width / 2.0, TOP, 22.0, WHITE);
+
+    for (index, boss) in simulation.enemies.bosses.iter().enumerate() {
+        let x = screen_width() / 2.0 - BOSS_BAR_WIDTH / 2.0;
+        let y = TOP + 12.0 + 14.0 * index as f32;
+        let health = boss.hit_points as f32 / boss.max_hit_points as f32;
+        draw_rectangle(x, y, BOSS_BAR_WIDTH * health, 8.0, RED);
+        draw_rectangle_lines(x, y, BOSS_BAR_WIDTH, 8.0, 1.0, GRAY);
+    }
+}
+
+pub fn draw_hud(simulation: &Simulation, scores: &Scores) {
+    const FONT_SIZE: f32 = 25.0;
+    const SCREEN_VERTICAL_POSITION: f32 = 35.0;
+
+    let (lives, score) = (simulation.lives, simulation.score);
+
+    let lives_text = if simulation.rules.loses_lives() { format!("Lives: {}", lives) } else { "Lives: Inf".to_string() };
+    let text_dimensions = measure_text(lives_text.as_str(), None, 25, 1.0);
+    draw_text(
+        lives_text.as_str(),
+        30.0,
+        SCREEN_VERTICAL_POSITION,
+        FONT_SIZE,
+        WHITE,
+    );
+
+    draw_text(
+        format!("Score: {}", score).as_str(),
+        text_dimensions.width + 50.0,
+        SCREEN_VERTICAL_POSITION,
+        FONT_SIZE,
+        WHITE,
+    );
+
+    let high_score_text = format!("High score: {}", scores.high_score);
+    let text_dimensions = measure_text(high_score_text.as_str(), None, 25, 1.0);
+    draw_text(
+        high_score_text.as_str(),
+        screen_width() - text_dimensions.width - 10.0,
+        SCREEN_VERTICAL_POSITION,
+        FONT_SIZE,
+        WHITE,
+    );
+
+    draw_combo_meter(&simulation.combo);
+    draw_mode_status(simulation);
+}
@@ -0,0 +1,96 @@
+use macroquad::input::{is_key_down, is_key_pressed, KeyCode};
+
+use crate::gamepad::{GamepadButton, Gamepads};
+
+/// Controls of the ship for one frame, from the keyboard or a gamepad.
+///
+/// Runs only read their inputs through this type, so a run can be replayed
+/// from the inputs it recorded.
+#[derive(PartialEq, Eq, Default, Clone, Copy, Debug)]
+pub struct PlayerInput(u8);
+
+impl PlayerInput {
+    pub const LEFT: PlayerInput = PlayerInput(1);
+    pub const RIGHT: PlayerInput = PlayerInput(1 << 1);
+    pub const UP: PlayerInput = PlayerInput(1 << 2);
+    pub const DOWN: PlayerInput = PlayerInput(1 << 3);
+    /// Fire was pressed this frame.
+    pub const FIRE: PlayerInput = PlayerInput(1 << 4);
+
+    pub fn read(gamepads: &Gamepads) -> Self {
+        let stick = gamepads.left_stick();
+        let mut input = PlayerInput::default();
+
+        if is_key_down(KeyCode::Left) || gamepads.is_down(GamepadButton::DPadLeft) || stick.x < 0.0 {
+            input = input.with(PlayerInput::LEFT);
+        }
+        if is_key_down(KeyCode::Right) || gamepads.is_down(GamepadButton::DPadRight) || stick.x > 0.0 {
+            input = input.with(PlayerInput::RIGHT);
+        }
+        if is_key_down(KeyCode::Up) || gamepads.is_down(GamepadButton::DPadUp) || stick.y < 0.0 {
+            input = input.with(PlayerInput::UP);
+        }
+        if is_key_down(KeyCode::Down) || gamepads.is_down(GamepadButton::DPadDown) || stick.y > 0.0 {
+            input = input.with(PlayerInput::DOWN);
+        }
+        if is_key_pressed(KeyCode::Space) || gamepads.is_pressed(GamepadButton::South) {
+            input = input.with(PlayerInput::FIRE);
+        }
+
+        input
+    }
+
+    pub fn from_bits(bits: u8) -> Self {
+        PlayerInput(bits)
+    }
+
+    pub fn bits(&self) -> u8 {
+        self.0
+    }
+
+    pub fn with(self, other: PlayerInput) -> Self {
+        PlayerInput(self.0 | other.0)
+    }
+
+    pub fn contains(&self, other: PlayerInput) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// The same input with left and right swapped.
+    pub fn mirrored(self) -> Self {
+        let sides = Self::LEFT.0 | Self::RIGHT.0;
+        let mut mirrored = PlayerInput(self.0 & !sides);
+        if self.contains(Self::LEFT) {
+            mirrored = mirrored.with(Self::RIGHT);
+        }
+        if self.contains(Self::RIGHT) {
+            mirrored = mirrored.with(Self::LEFT);
+        }
+
+        mirrored
+    }
+}
+
+/// Actions of the menus, from the keyboard or a gamepad.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum MenuAction {
+    Up,
+    Down,
+    Left,
+    Right,
+    Confirm,
+    Back,
+}
+
+impl MenuAction {
+    pub fn is_pressed(self, gamepads: &Gamepads) -> bool {
+        match self {
+            MenuAction::Up => is_key_pressed(KeyCode::Up) || gamepads.is_pressed(GamepadButton::DPadUp),
+            MenuAction::Down => is_key_pressed(KeyCode::Down) || gamepads.is_pressed(GamepadButton::DPadDown),
+            MenuAction::Left => is_key_pressed(KeyCode::Left) || gamepads.is_pressed(GamepadButton::DPadLeft),
+            MenuAction::Right => is_key_pressed(KeyCode::Right) || gamepads.is_pressed(GamepadButton::DPadRight),
+            MenuAction::Confirm => is_key_pressed(KeyCode::Enter) || gamepads.is_pressed(GamepadButton::South),
+            MenuAction::Back => is_key_pressed(KeyCode::Escape) || gamepads.is_pressed(GamepadButton::East),
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use crate::{difficulty::Difficulty, game_mode::GameMode, mutators::Mutators};
+
+/// Entries kept per leaderboard.
+pub const LEADERBOARD_SIZE: usize = 10;
+
+/// Runs only compete with runs of the same mode and difficulty.
+#[derive(PartialEq, Eq, Hash, Default, Clone, Copy, Debug)]
+pub struct LeaderboardCategory {
+    pub mode: GameMode,
+    pub difficulty: Difficulty,
+}
+
+impl LeaderboardCategory {
+    pub fn all() -> impl Iterator<Item = LeaderboardCategory> {
+        GameMode::ALL.into_iter().flat_map(|mode| {
+            Difficulty::ALL.into_iter().map(move |difficulty| LeaderboardCategory { mode, difficulty })
+        })
+    }
+
+    pub fn name(&self) -> String {
+        format!("{} - {}", self.mode.name(), self.difficulty.name())
+    }
+}
+
+#[derive(Default, Clone, Debug)]
+pub struct LeaderboardEntry {
+    /// Initials of the player.
+    pub name: String,
+    pub score: u32,
+    pub wave: u32,
+    /// Seconds survived.
+    pub duration: f32,
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    pub seed: u64,
+    /// Mutators the run was played with.
+    pub mutators: Mutators,
+}
+
+impl LeaderboardEntry {
+    /// Date of the run as "YYYY-MM-DD".
+    pub fn date_text(&self) -> String {
+        let (year, month, day) = civil_from_days((self.timestamp / 86_400) as i64);
+        format!("{:04}-{:02}-{:02}", year, month, day)
+    }
+
+    fn to_line(&self, category: LeaderboardCategory) -> String {
+        format!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            category.mode.name(),
+            category.difficulty.name(),
+            self.name,
+            self.score,
+            self.wave,
+            self.duration,
+            self.timestamp,
+            self.seed,
+            self.mutators.to_text(),
+        )
+    }
+
+    fn from_line(line: &str) -> Option<(LeaderboardCategory, Self)> {
+        let mut fields = line.split('\t');
+
+        let category = LeaderboardCategory {
+            mode: GameMode::from_name(fields.next()?)?,
+            difficulty: Difficulty::from_name(fields.next()?)?,
+        };
+        let entry = LeaderboardEntry {
+            name: fields.next()?.to_string(),
+            score: fields.next()?.parse().ok()?,
+            wave: fields.next()?.parse().ok()?,
+            duration: fields.next()?.parse().ok()?,
+            timestamp: fields.next()?.parse().ok()?,
+            seed: fields.next()?.parse().ok()?,
+            // Entries from before mutators have none
+            mutators: match fields.next() {
+                Some(text) => Mutators::from_text(text)?,
+                None => Mutators::default(),
+            },
+        };
+
+        Some((category, entry))
+    }
+}
+
+/// Best runs of every category, highest score first.
+#[derive(Default)]
+pub struct Leaderboards {
+    tables: Vec<(LeaderboardCategory, Vec<LeaderboardEntry>)>,
+}
+
+impl Leaderboards {
+    /// Reads leaderboards written by `to_text`, skipping the lines it can't
+    /// read.
+    pub fn from_text(text: &str) -> Self {
+        let mut leaderboards = Self::default();
+        for (category, entry) in text.lines().filter_map(LeaderboardEntry::from_line) {
+            leaderboards.insert(category, entry);
+        }
+
+        leaderboards
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for (category, entries) in self.tables.iter() {
+            for entry in entries.iter() {
+                text.push_str(&entry.to_line(*category));
+                text.push('\n');
+            }
+        }
+
+        text
+    }
+
+    pub fn entries(&self, category: LeaderboardCategory) -> &[LeaderboardEntry] {
+        self.tables
+            .iter()
+            .find(|(table_category, _)| *table_category == category)
+            .map_or(&[], |(_, entries)| entries.as_slice())
+    }
+
+    /// Whether a run with `score` would enter the leaderboard.
+    pub fn qualifies(&self, category: LeaderboardCategory, score: u32) -> bool {
+        let entries = self.entries(category);
+        score > 0 && (entries.len() < LEADERBOARD_SIZE || entries.last().is_some_and(|last| score > last.score))
+    }
+
+    /// Adds the entry in score order, returns its position if it made the cut.
+    pub fn insert(&mut self, category: LeaderboardCategory, entry: LeaderboardEntry) -> Option<usize> {
+        let index = match self.tables.iter().position(|(table_category, _)| *table_category == category) {
+            Some(index) => index,
+            None => {
+                self.tables.push((category, Vec::with_capacity(LEADERBOARD_SIZE + 1)));
+                self.tables.len() - 1
+            }
+        };
+        let entries = &mut self.tables[index].1;
+
+        // Ties keep the older entry first
+        let position = entries.iter().position(|other| entry.score > other.score).unwrap_or(entries.len());
+        if position >= LEADERBOARD_SIZE {
+            return None;
+        }
+
+        entries.insert(position, entry);
+        entries.truncate(LEADERBOARD_SIZE);
+
+        Some(position)
+    }
+}
+
+/// Year, month and day of a number of days since 1970-01-01.
+///
+/// From Howard Hinnant's `civil_from_days`.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_days_to_dates() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+        assert_eq!(civil_from_days(59), (1970, 3, 1));
+        // Leap days, including the one of a year divisible by 400
+        assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+        assert_eq!(civil_from_days(11_017), (2000, 3, 1));
+        assert_eq!(civil_from_days(19_782), (2024, 2, 29));
+        assert_eq!(civil_from_days(20_370), (2025, 10, 9));
+        // 2100 is not a leap year
+        assert_eq!(civil_from_days(47_540), (2100, 2, 28));
+        assert_eq!(civil_from_days(47_541), (2100, 3, 1));
+    }
+
+    #[test]
+    fn consecutive_days_give_consecutive_dates() {
+        let mut previous = civil_from_days(-800);
+        for days in -799..60_000 {
+            let (year, month, day) = civil_from_days(days);
+            let next_day = (previous.0, previous.1, previous.2 + 1);
+            let next_month = (previous.0, previous.1 + 1, 1);
+            let next_year = (previous.0 + 1, 1, 1);
+
+            assert!([next_day, next_month, next_year].contains(&(year, month, day)), "{}", days);
+            assert!((1..=31).contains(&day) && (1..=12).contains(&month));
+            previous = (year, month, day);
+        }
+    }
+
+    #[test]
+    fn date_text_uses_the_day_of_the_timestamp() {
+        let entry = |timestamp| LeaderboardEntry { timestamp, ..Default::default() };
+
+        assert_eq!(entry(1_760_000_000).date_text(), "2025-10-09");
+        assert_eq!(entry(86_399).date_text(), "1970-01-01");
+        assert_eq!(entry(86_400).date_text(), "1970-01-02");
+    }
+}
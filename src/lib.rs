@@ -0,0 +1,51 @@
+pub mod collidable;
+pub mod collision_layers;
+pub mod collision_grid;
+pub mod collision_mask;
+pub mod shape;
+pub mod enemy;
+pub mod enemies;
+pub mod bullet;
+pub mod hero;
+pub mod game;
+pub mod constants;
+pub mod scores;
+pub mod shaders;
+pub mod particles;
+pub mod sprites_config;
+pub mod sound_config;
+pub mod menu;
+pub mod hud;
+pub mod scene;
+pub mod scenes;
+pub mod world;
+pub mod components;
+pub mod systems;
+pub mod pool;
+pub mod debug_overlay;
+pub mod transition;
+pub mod run_stats;
+pub mod gamepad;
+pub mod input;
+pub mod replay;
+pub mod game_mode;
+pub mod difficulty;
+pub mod leaderboards;
+pub mod toasts;
+pub mod save_file;
+pub mod storage;
+pub mod game_settings;
+pub mod json;
+pub mod http;
+pub mod online_leaderboard;
+pub mod simulation;
+pub mod verification;
+pub mod achievements;
+pub mod lifetime_stats;
+pub mod combo;
+pub mod popups;
+pub mod director;
+pub mod mutators;
+pub mod practice;
+pub mod run_rules;
+pub mod campaign;
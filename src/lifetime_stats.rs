@@ -0,0 +1,189 @@
+use crate::{enemy::EnemySize, game_mode::GameMode, run_stats::RunStats};
+
+/// Totals of every run played, kept between sessions.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct LifetimeStats {
+    pub runs: u32,
+    /// Seconds played, not counting pauses.
+    pub time_played: f32,
+    pub shots_fired: u32,
+    pub shots_hit: u32,
+    /// Indexed by `EnemySize`.
+    pub enemies_destroyed_by_size: [u32; 3],
+    pub lives_lost: u32,
+    /// Pixels travelled by the ship.
+    pub distance_flown: f32,
+    pub best_score: u32,
+    pub best_combo: u32,
+    /// Furthest wave reached, unlocking it for practice.
+    pub best_wave: u32,
+    /// Furthest campaign level reached, unlocking it for practice.
+    pub best_level: u32,
+}
+
+impl LifetimeStats {
+    pub fn add_run(&mut self, score: u32, run: &RunStats) {
+        self.runs += 1;
+        self.time_played += run.time_survived;
+        self.shots_fired += run.shots_fired;
+        self.shots_hit += run.shots_hit;
+        for (total, destroyed) in self.enemies_destroyed_by_size.iter_mut().zip(run.enemies_destroyed_by_size) {
+            *total += destroyed;
+        }
+        self.lives_lost += run.lives_lost;
+        self.distance_flown += run.distance_flown;
+        self.best_score = self.best_score.max(score);
+        self.best_combo = self.best_combo.max(run.max_combo);
+    }
+
+    /// Unlocks `wave` of `mode` for practice.
+    pub fn reached(&mut self, mode: GameMode, wave: u32) {
+        if mode.has_levels() {
+            self.best_level = self.best_level.max(wave);
+        } else {
+            self.best_wave = self.best_wave.max(wave);
+        }
+    }
+
+    /// One "name value" line per stat.
+    pub fn to_text(&self) -> String {
+        let [small, medium, big] = self.enemies_destroyed_by_size;
+
+        format!(
+            "runs {}\ntime_played {}\nshots_fired {}\nshots_hit {}\nsmall_destroyed {}\nmedium_destroyed {}\nbig_destroyed {}\nlives_lost {}\ndistance_flown {}\nbest_score {}\nbest_combo {}\nbest_wave {}\nbest_level {}\n",
+            self.runs,
+            self.time_played,
+            self.shots_fired,
+            self.shots_hit,
+            small,
+            medium,
+            big,
+            self.lives_lost,
+            self.distance_flown,
+            self.best_score,
+            self.best_combo,
+            self.best_wave,
+            self.best_level,
+        )
+    }
+
+    /// Reads the stats written by `to_text`, missing or invalid ones start
+    /// from zero.
+    pub fn from_text(text: &str) -> Self {
+        let mut stats = Self::default();
+
+        for line in text.lines() {
+            let Some((name, value)) = line.split_once(' ') else {
+                continue;
+            };
+            let count = || value.parse().unwrap_or(0);
+            let seconds = || value.parse().unwrap_or(0.0);
+
+            match name {
+                "runs" => stats.runs = count(),
+                "time_played" => stats.time_played = seconds(),
+                "shots_fired" => stats.shots_fired = count(),
+                "shots_hit" => stats.shots_hit = count(),
+                "small_destroyed" => stats.enemies_destroyed_by_size[EnemySize::Small as usize] = count(),
+                "medium_destroyed" => stats.enemies_destroyed_by_size[EnemySize::Medium as usize] = count(),
+                "big_destroyed" => stats.enemies_destroyed_by_size[EnemySize::Big as usize] = count(),
+                "lives_lost" => stats.lives_lost = count(),
+                "distance_flown" => stats.distance_flown = seconds(),
+                "best_score" => stats.best_score = count(),
+                "best_combo" => stats.best_combo = count(),
+                "best_wave" => stats.best_wave = count(),
+                "best_level" => stats.best_level = count(),
+                _ => {}
+            }
+        }
+
+        stats
+    }
+}
+
+/// How the value of a stat is shown.
+#[derive(Clone, Copy, Debug)]
+pub enum StatUnit {
+    Count,
+    Seconds,
+    /// From 0 to 1.
+    Ratio,
+    Pixels,
+}
+
+/// A stat for the last run and for every run.
+#[derive(Clone, Copy, Debug)]
+pub struct StatRow {
+    pub name: &'static str,
+    /// Column name in the CSV export.
+    pub key: &'static str,
+    pub unit: StatUnit,
+    pub last_run: Option<f32>,
+    pub lifetime: f32,
+}
+
+impl StatRow {
+    pub fn format(&self, value: Option<f32>) -> String {
+        let Some(value) = value else {
+            return "-".to_string();
+        };
+
+        match self.unit {
+            StatUnit::Count | StatUnit::Pixels => format!("{:.0}", value),
+            StatUnit::Seconds => {
+                let seconds = value as u32;
+                format!("{}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60)
+            }
+            StatUnit::Ratio => format!("{:.1}%", value * 100.0),
+        }
+    }
+}
+
+fn accuracy(shots_hit: u32, shots_fired: u32) -> Option<f32> {
+    (shots_fired > 0).then(|| shots_hit as f32 / shots_fired as f32)
+}
+
+/// Rows of the stats screen and of the CSV export.
+pub fn stats_table(last_run: Option<&RunStats>, lifetime: &LifetimeStats) -> Vec<StatRow> {
+    let row = |name, key, unit, run: fn(&RunStats) -> Option<f32>, lifetime: f32| StatRow {
+        name,
+        key,
+        unit,
+        last_run: last_run.and_then(run),
+        lifetime,
+    };
+    let kills = |size: EnemySize| lifetime.enemies_destroyed_by_size[size as usize] as f32;
+
+    vec![
+        row("Runs", "runs", StatUnit::Count, |_| Some(1.0), lifetime.runs as f32),
+        row("Time played", "time_played", StatUnit::Seconds, |run| Some(run.time_survived), lifetime.time_played),
+        row("Shots fired", "shots_fired", StatUnit::Count, |run| Some(run.shots_fired as f32), lifetime.shots_fired as f32),
+        row("Hits", "shots_hit", StatUnit::Count, |run| Some(run.shots_hit as f32), lifetime.shots_hit as f32),
+        StatRow {
+            name: "Accuracy",
+            key: "accuracy",
+            unit: StatUnit::Ratio,
+            last_run: last_run.and_then(|run| accuracy(run.shots_hit, run.shots_fired)),
+            lifetime: accuracy(lifetime.shots_hit, lifetime.shots_fired).unwrap_or(0.0),
+        },
+        row("Small kills", "small_destroyed", StatUnit::Count, |run| Some(run.enemies_destroyed_of(EnemySize::Small) as f32), kills(EnemySize::Small)),
+        row("Medium kills", "medium_destroyed", StatUnit::Count, |run| Some(run.enemies_destroyed_of(EnemySize::Medium) as f32), kills(EnemySize::Medium)),
+        row("Big kills", "big_destroyed", StatUnit::Count, |run| Some(run.enemies_destroyed_of(EnemySize::Big) as f32), kills(EnemySize::Big)),
+        row("Lives lost", "lives_lost", StatUnit::Count, |run| Some(run.lives_lost as f32), lifetime.lives_lost as f32),
+        row("Distance flown", "distance_flown", StatUnit::Pixels, |run| Some(run.distance_flown), lifetime.distance_flown),
+        row("Best combo", "best_combo", StatUnit::Count, |run| Some(run.max_combo as f32), lifetime.best_combo as f32),
+        row("Best score", "best_score", StatUnit::Count, |_| None, lifetime.best_score as f32),
+    ]
+}
+
+/// The stats as CSV, one row per stat with raw values: seconds, pixels and
+/// ratios from 0 to 1.
+pub fn stats_csv(last_run: Option<&RunStats>, lifetime: &LifetimeStats) -> String {
+    let mut csv = "stat,last_run,lifetime\n".to_string();
+    for row in stats_table(last_run, lifetime) {
+        let last_run = row.last_run.map_or_else(String::new, |value| value.to_string());
+        csv.push_str(&format!("{},{},{}\n", row.key, last_run, row.lifetime));
+    }
+
+    csv
+}
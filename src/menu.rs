@@ -7,6 +7,8 @@ use macroquad::{
     window::{screen_height, screen_width}
 };
 
+use crate::{gamepad::Gamepads, input::MenuAction};
+
 pub struct Menu;
 
 impl Menu {
@@ -62,27 +64,104 @@ impl Menu {
         root_ui().push_skin(&ui_skin);
     }
 
-    pub fn main_menu<C: FnMut(), P: FnMut()>(mut on_close_click: C, mut on_play_click: P) {
-        let window_size = vec2(370.0, 320.0);
+    /// Key of the letter in parentheses of a button label, as in "(P)lay".
+    fn shortcut(label: &str) -> Option<KeyCode> {
+        let letter = label.split_once('(')?.1.chars().next()?.to_ascii_uppercase();
+
+        let key = match letter {
+            'A' => KeyCode::A, 'B' => KeyCode::B, 'C' => KeyCode::C, 'D' => KeyCode::D,
+            'E' => KeyCode::E, 'F' => KeyCode::F, 'G' => KeyCode::G, 'H' => KeyCode::H,
+            'I' => KeyCode::I, 'J' => KeyCode::J, 'K' => KeyCode::K, 'L' => KeyCode::L,
+            'M' => KeyCode::M, 'N' => KeyCode::N, 'O' => KeyCode::O, 'P' => KeyCode::P,
+            'Q' => KeyCode::Q, 'R' => KeyCode::R, 'S' => KeyCode::S, 'T' => KeyCode::T,
+            'U' => KeyCode::U, 'V' => KeyCode::V, 'W' => KeyCode::W, 'X' => KeyCode::X,
+            'Y' => KeyCode::Y, 'Z' => KeyCode::Z,
+            _ => return None,
+        };
+
+        Some(key)
+    }
+
+    /// Window with a title and a column of buttons, returns the index of the
+    /// button clicked or whose shortcut was pressed.
+    ///
+    /// Windows that aren't `interactive` are only drawn, for scenes frozen
+    /// by a transition.
+    pub fn list_window(id: u64, title: &str, items: &[&str], width: f32, interactive: bool) -> Option<usize> {
+        Self::window(id, title, items, width, None, interactive)
+    }
+
+    /// `list_window` that can also be navigated with the arrows or a gamepad,
+    /// `focus` being the index of the focused button.
+    pub fn focus_list_window(id: u64, title: &str, items: &[&str], width: f32, focus: &mut usize, gamepads: &Gamepads, interactive: bool) -> Option<usize> {
+        if !interactive {
+            return Self::window(id, title, items, width, Some(*focus), false);
+        }
+
+        let count = items.len();
+        if MenuAction::Up.is_pressed(gamepads) {
+            *focus = (*focus + count - 1) % count;
+        }
+        if MenuAction::Down.is_pressed(gamepads) {
+            *focus = (*focus + 1) % count;
+        }
+
+        let selected = Self::window(id, title, items, width, Some(*focus), true)
+            .or_else(|| MenuAction::Confirm.is_pressed(gamepads).then_some(*focus));
+
+        if let Some(index) = selected {
+            *focus = index;
+        }
+
+        selected
+    }
+
+    fn window(id: u64, title: &str, items: &[&str], width: f32, focus: Option<usize>, interactive: bool) -> Option<usize> {
+        let window_size = vec2(width, 120.0 + 100.0 * items.len() as f32);
+        let mut selected = None;
 
         root_ui().window(
-            hash!(),
+            id,
                 vec2(
                     screen_width() / 2.0 - window_size.x / 2.0,
                     screen_height() / 2.0 - window_size.y / 2.0,
                 ),
                 window_size,
                 |ui| {
-                    ui.label(vec2(80.0, -34.0), "Main Menu");
-                    if ui.button(vec2(65.0, 25.0), "(P)lay") ||
-                        is_key_pressed(KeyCode::P) {
-                        on_play_click();
-                    }
-                    if ui.button(vec2(65.0, 125.0), "(Q)uit") ||
-                        is_key_pressed(KeyCode::Q) {
-                        on_close_click();
+                    ui.label(vec2(80.0, -34.0), title);
+                    for (index, item) in items.iter().enumerate() {
+                        let top = 25.0 + 100.0 * index as f32;
+                        if focus == Some(index) {
+                            ui.label(vec2(30.0, top + 20.0), ">");
+                        }
+
+                        let clicked = ui.button(vec2(65.0, top), *item);
+                        let pressed = Self::shortcut(item).is_some_and(is_key_pressed);
+                        if interactive && (clicked || pressed) {
+                            selected = Some(index);
+                        }
                     }
                 },
             );
+
+        selected
     }
-}
\ No newline at end of file
+
+    pub fn main_menu(interactive: bool) -> Option<MainMenuItem> {
+        const ITEMS: [MainMenuItem; 5] = [MainMenuItem::Play, MainMenuItem::Practice, MainMenuItem::Settings, MainMenuItem::Records, MainMenuItem::Quit];
+
+        Self::list_window(hash!(), "Main Menu", &["(P)lay", "Pr(a)ctice", "(S)ettings", "(R)ecords", "(Q)uit"], 420.0, interactive)
+            .map(|index| ITEMS[index])
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum MainMenuItem {
+    Play,
+    /// Unranked runs from an unlocked wave or level.
+    Practice,
+    Settings,
+    /// Leaderboards, achievements and stats.
+    Records,
+    Quit,
+}
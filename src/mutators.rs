@@ -0,0 +1,153 @@
+/// Optional rule of a run, any number of them can be combined.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Mutator {
+    OneHitKills,
+    DoubleEnemySpeed,
+    GiantEnemies,
+    TinyHero,
+    MirroredControls,
+    ScreenWrap,
+    RicochetBullets,
+    NoHud,
+}
+
+impl Mutator {
+    pub const ALL: [Mutator; 8] = [
+        Mutator::OneHitKills,
+        Mutator::DoubleEnemySpeed,
+        Mutator::GiantEnemies,
+        Mutator::TinyHero,
+        Mutator::MirroredControls,
+        Mutator::ScreenWrap,
+        Mutator::RicochetBullets,
+        Mutator::NoHud,
+    ];
+
+    /// Name kept in replays and leaderboards.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Mutator::OneHitKills => "one_hit_kills",
+            Mutator::DoubleEnemySpeed => "double_enemy_speed",
+            Mutator::GiantEnemies => "giant_enemies",
+            Mutator::TinyHero => "tiny_hero",
+            Mutator::MirroredControls => "mirrored_controls",
+            Mutator::ScreenWrap => "screen_wrap",
+            Mutator::RicochetBullets => "ricochet_bullets",
+            Mutator::NoHud => "no_hud",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|mutator| mutator.id() == id)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Mutator::OneHitKills => "One-hit kills",
+            Mutator::DoubleEnemySpeed => "Double enemy speed",
+            Mutator::GiantEnemies => "Giant enemies",
+            Mutator::TinyHero => "Tiny hero",
+            Mutator::MirroredControls => "Mirrored controls",
+            Mutator::ScreenWrap => "Screen wrap",
+            Mutator::RicochetBullets => "Ricochet bullets",
+            Mutator::NoHud => "No HUD",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Mutator::OneHitKills => "Any hit destroys the ship, and the bosses",
+            Mutator::DoubleEnemySpeed => "Enemies fall twice as fast",
+            Mutator::GiantEnemies => "Enemies are half again as big",
+            Mutator::TinyHero => "The ship is half its size",
+            Mutator::MirroredControls => "Left and right are swapped",
+            Mutator::ScreenWrap => "The ship wraps around the edges of the screen",
+            Mutator::RicochetBullets => "Bolts bounce off the first enemy they hit",
+            Mutator::NoHud => "Fly without the score, lives and combo",
+        }
+    }
+
+    /// Factor of the points of every kill, above 1 for the mutators that
+    /// make runs harder.
+    pub fn score_multiplier(&self) -> f32 {
+        match self {
+            Mutator::OneHitKills => 1.5,
+            Mutator::DoubleEnemySpeed => 1.5,
+            Mutator::GiantEnemies => 1.2,
+            Mutator::TinyHero => 0.8,
+            Mutator::MirroredControls => 1.3,
+            Mutator::ScreenWrap => 0.9,
+            Mutator::RicochetBullets => 0.8,
+            Mutator::NoHud => 1.1,
+        }
+    }
+
+    const fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+/// Set of mutators of a run.
+#[derive(PartialEq, Eq, Default, Clone, Copy, Debug)]
+pub struct Mutators(u8);
+
+impl Mutators {
+    pub fn contains(&self, mutator: Mutator) -> bool {
+        self.0 & mutator.bit() != 0
+    }
+
+    pub fn toggle(&mut self, mutator: Mutator) {
+        self.0 ^= mutator.bit();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Mutator> + '_ {
+        Mutator::ALL.into_iter().filter(|mutator| self.contains(*mutator))
+    }
+
+    /// Product of the score multipliers of every mutator.
+    pub fn score_multiplier(&self) -> f32 {
+        self.iter().map(|mutator| mutator.score_multiplier()).product()
+    }
+
+    /// Factor of the size of the enemies.
+    pub fn enemy_scale(&self) -> f32 {
+        if self.contains(Mutator::GiantEnemies) { 1.5 } else { 1.0 }
+    }
+
+    /// Factor of the speed of the enemies.
+    pub fn enemy_speed(&self) -> f32 {
+        if self.contains(Mutator::DoubleEnemySpeed) { 2.0 } else { 1.0 }
+    }
+
+    /// Factor of the size of the ship.
+    pub fn hero_scale(&self) -> f32 {
+        if self.contains(Mutator::TinyHero) { 0.5 } else { 1.0 }
+    }
+
+    /// Ids separated by commas, `none` for an empty set.
+    pub fn to_text(&self) -> String {
+        if self.is_empty() {
+            return "none".to_string();
+        }
+
+        self.iter().map(|mutator| mutator.id()).collect::<Vec<_>>().join(",")
+    }
+
+    /// Reads a set written by `to_text`.
+    pub fn from_text(text: &str) -> Option<Self> {
+        let mut mutators = Self::default();
+        if text == "none" {
+            return Some(mutators);
+        }
+
+        for id in text.split(',') {
+            mutators.0 |= Mutator::from_id(id)?.bit();
+        }
+
+        Some(mutators)
+    }
+}
@@ -0,0 +1,273 @@
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use macroquad::color::{GREEN, ORANGE};
+
+use crate::{
+    constants::{MAX_ONLINE_QUEUE, ONLINE_LEADERBOARD_URL, ONLINE_QUEUE_FILE_NAME},
+    http,
+    json::Json,
+    leaderboards::{LeaderboardCategory, LeaderboardEntry},
+    mutators::Mutators,
+    replay::Replay,
+    storage::{self, Storage},
+    toasts::Toasts,
+};
+
+/// Seconds before retrying a failed submission, doubled after each failure.
+const FIRST_RETRY_DELAY: f32 = 5.0;
+const MAX_RETRY_DELAY: f32 = 300.0;
+
+/// Global rankings of a category, as last fetched.
+#[derive(Clone, Debug)]
+pub enum OnlineScores {
+    Loading,
+    Loaded(Vec<LeaderboardEntry>),
+    Failed(String),
+}
+
+/// Outcome of a request, sent back by the thread that made it.
+enum Reply {
+    /// `None` when the submission should be retried later.
+    Submitted(Option<Result<u32, String>>),
+    Fetched(LeaderboardCategory, Result<Vec<LeaderboardEntry>, String>),
+}
+
+/// Client of the online leaderboard service, see
+/// `docs/online-leaderboard.md` for the protocol.
+///
+/// Requests run on their own threads, so the game never waits for the
+/// network. Submissions are queued in the storage and sent one at a time,
+/// runs played offline are sent once the service can be reached. Browsers
+/// can't reach the service, nothing is submitted there.
+pub struct OnlineLeaderboard {
+    url: String,
+    storage: Box<dyn Storage>,
+    /// Request bodies of the submissions not sent yet, oldest first.
+    queue: Vec<String>,
+    sending: bool,
+    retry_delay: f32,
+    retry_timer: f32,
+    scores: Option<(LeaderboardCategory, OnlineScores)>,
+    sender: Sender<Reply>,
+    receiver: Receiver<Reply>,
+}
+
+impl Default for OnlineLeaderboard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OnlineLeaderboard {
+    pub fn new() -> Self {
+        let mut storage = storage::platform_storage();
+        let queue = if Self::is_supported() {
+            storage
+                .read(ONLINE_QUEUE_FILE_NAME)
+                .ok()
+                .flatten()
+                .map(|text| text.lines().map(str::to_string).collect())
+                .unwrap_or_default()
+        } else {
+            // Frees the space taken by the queues of older versions
+            let _ = storage.remove(ONLINE_QUEUE_FILE_NAME);
+            Vec::new()
+        };
+        let (sender, receiver) = mpsc::channel();
+
+        OnlineLeaderboard {
+            url: std::env::var("LEADERBOARD_URL").unwrap_or_else(|_| ONLINE_LEADERBOARD_URL.to_string()),
+            storage,
+            queue,
+            sending: false,
+            retry_delay: FIRST_RETRY_DELAY,
+            retry_timer: 0.0,
+            scores: None,
+            sender,
+            receiver,
+        }
+    }
+
+    /// Whether the server can be reached from this platform, the web build
+    /// has no sockets.
+    pub fn is_supported() -> bool {
+        cfg!(not(target_arch = "wasm32"))
+    }
+
+    /// Submissions waiting to be sent.
+    pub fn pending(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Queues a run, it is sent on the next `update`. The oldest waiting
+    /// submission is dropped when the queue is full.
+    pub fn submit(&mut self, category: LeaderboardCategory, entry: &LeaderboardEntry, replay: &Replay, toasts: &mut Toasts) {
+        if !Self::is_supported() {
+            return;
+        }
+
+        let body = Json::Object(vec![
+            ("game_version".to_string(), replay.game_version.as_str().into()),
+            ("mode".to_string(), category.mode.name().into()),
+            ("difficulty".to_string(), category.difficulty.name().into()),
+            ("name".to_string(), entry.name.as_str().into()),
+            ("score".to_string(), entry.score.into()),
+            ("wave".to_string(), entry.wave.into()),
+            ("duration".to_string(), entry.duration.into()),
+            ("seed".to_string(), entry.seed.to_string().into()),
+            ("mutators".to_string(), entry.mutators.to_text().into()),
+            ("replay".to_string(), replay.to_text().into()),
+        ]);
+
+        self.queue.push(body.to_string());
+        if self.queue.len() > MAX_ONLINE_QUEUE {
+            // The first submission may be on its way
+            self.queue.remove(if self.sending { 1 } else { 0 });
+        }
+        self.save_queue(toasts);
+        self.retry_timer = 0.0;
+    }
+
+    /// Global rankings of `category`, fetched the first time they are asked
+    /// for or when `refresh` is set.
+    pub fn scores(&mut self, category: LeaderboardCategory, refresh: bool) -> &OnlineScores {
+        let fetched = self.scores.as_ref().is_some_and(|(fetched, _)| *fetched == category);
+        if !fetched || refresh {
+            let url = format!(
+                "{}/scores?mode={}&difficulty={}",
+                self.url,
+                http::encode_query_value(category.mode.name()),
+                http::encode_query_value(category.difficulty.name()),
+            );
+            let sender = self.sender.clone();
+            run_request(move || {
+                let scores = http::send("GET", &url, None)
+                    .map_err(|error| error.to_string())
+                    .and_then(|response| parse_scores(response.status, &response.body));
+                let _ = sender.send(Reply::Fetched(category, scores));
+            });
+
+            self.scores = Some((category, OnlineScores::Loading));
+        }
+
+        &self.scores.as_ref().expect("the scores were just requested").1
+    }
+
+    /// Handles the replies of the requests and sends the next submission
+    /// when it is time to.
+    pub fn update(&mut self, delta_time: f32, toasts: &mut Toasts) {
+        while let Ok(reply) = self.receiver.try_recv() {
+            match reply {
+                Reply::Submitted(result) => self.submitted(result, toasts),
+                Reply::Fetched(category, scores) => {
+                    if self.scores.as_ref().is_some_and(|(fetched, _)| *fetched == category) {
+                        let scores = scores.map_or_else(OnlineScores::Failed, OnlineScores::Loaded);
+                        self.scores = Some((category, scores));
+                    }
+                }
+            }
+        }
+
+        self.retry_timer -= delta_time;
+        if self.sending || self.retry_timer > 0.0 {
+            return;
+        }
+
+        if let Some(body) = self.queue.first().cloned() {
+            self.sending = true;
+
+            let url = format!("{}/scores", self.url);
+            let sender = self.sender.clone();
+            run_request(move || {
+                let result = match http::send("POST", &url, Some(&body)) {
+                    Ok(response) => submission_result(response.status, &response.body),
+                    Err(_) => None,
+                };
+                let _ = sender.send(Reply::Submitted(result));
+            });
+        }
+    }
+
+    fn submitted(&mut self, result: Option<Result<u32, String>>, toasts: &mut Toasts) {
+        self.sending = false;
+
+        let Some(result) = result else {
+            self.retry_timer = self.retry_delay;
+            self.retry_delay = (self.retry_delay * 2.0).min(MAX_RETRY_DELAY);
+            return;
+        };
+
+        match result {
+            Ok(rank) => toasts.push(format!("Score submitted, global rank #{}", rank), GREEN),
+            Err(error) => toasts.push(format!("Score rejected: {}", error), ORANGE),
+        }
+
+        self.queue.remove(0);
+        self.save_queue(toasts);
+        self.retry_delay = FIRST_RETRY_DELAY;
+    }
+
+    fn save_queue(&mut self, toasts: &mut Toasts) {
+        let text: String = self.queue.iter().map(|body| format!("{}\n", body)).collect();
+        if let Err(error) = self.storage.write(ONLINE_QUEUE_FILE_NAME, &text) {
+            toasts.push(format!("Could not save the pending scores: {}", error), ORANGE);
+        }
+    }
+}
+
+/// Runs a blocking request away from the game loop.
+///
+/// Browsers have no threads, the request runs in place there and fails right
+/// away as sockets aren't supported either.
+fn run_request<F: FnOnce() + Send + 'static>(request: F) {
+    #[cfg(not(target_arch = "wasm32"))]
+    std::thread::spawn(request);
+
+    #[cfg(target_arch = "wasm32")]
+    request();
+}
+
+fn error_message(body: &str) -> String {
+    Json::parse(body)
+        .ok()
+        .and_then(|json| json.get("error").and_then(Json::as_str).map(str::to_string))
+        .unwrap_or_else(|| "unknown error".to_string())
+}
+
+/// Rank of an accepted submission, the error of a rejected one, `None` when
+/// it should be sent again.
+fn submission_result(status: u16, body: &str) -> Option<Result<u32, String>> {
+    match status {
+        200..=299 => Some(Ok(Json::parse(body).ok().and_then(|json| json.get("rank")?.as_u32()).unwrap_or(0))),
+        400 | 409 => Some(Err(error_message(body))),
+        _ => None,
+    }
+}
+
+fn parse_scores(status: u16, body: &str) -> Result<Vec<LeaderboardEntry>, String> {
+    if status != 200 {
+        return Err(error_message(body));
+    }
+
+    let json = Json::parse(body)?;
+    let scores = json.get("scores").and_then(Json::as_array).ok_or("missing scores")?;
+
+    scores
+        .iter()
+        .map(|score| {
+            Some(LeaderboardEntry {
+                name: score.get("name")?.as_str()?.to_string(),
+                score: score.get("score")?.as_u32()?,
+                wave: score.get("wave")?.as_u32()?,
+                duration: score.get("duration")?.as_f64()? as f32,
+                timestamp: score.get("timestamp")?.as_f64()? as u64,
+                seed: score.get("seed")?.as_str()?.parse().ok()?,
+                mutators: match score.get("mutators") {
+                    Some(mutators) => Mutators::from_text(mutators.as_str()?)?,
+                    None => Mutators::default(),
+                },
+            })
+        })
+        .collect::<Option<_>>()
+        .ok_or_else(|| "invalid score".to_string())
+}
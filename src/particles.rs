@@ -1,21 +1,35 @@
 use macroquad::{color::{ORANGE, RED}, math::{vec2, Vec2}, texture::Texture2D};
 use macroquad_particles::{AtlasConfig, ColorCurve, Emitter, EmitterConfig};
 
-pub struct Particles {
-    pub explosions: Vec<(Emitter, Vec2)>,
+use crate::{constants::MAX_EXPLOSIONS, pool::Pool};
+
+/// Seconds an explosion keeps its emitter, long enough for every particle to fade.
+const EXPLOSION_DURATION: f32 = 1.0;
+
+pub struct Explosion {
+    emitter: Emitter,
+    position: Vec2,
+    remaining: f32,
 }
 
-impl Default for Particles {
-    fn default() -> Self {
-        Self::new()
-    }
+pub struct Particles {
+    pub explosions: Pool<Explosion>,
 }
 
 impl Particles {
 
-    pub fn new() -> Particles {
+    /// Creates every explosion emitter up front so kills don't allocate.
+    pub fn new(texture: &Texture2D) -> Particles {
         Particles {
-            explosions: vec![],
+            explosions: Pool::new(MAX_EXPLOSIONS, || Explosion {
+                emitter: Emitter::new(EmitterConfig {
+                    emitting: false,
+                    texture: Some(texture.clone()),
+                    ..Self::get_explosion_emitter()
+                }),
+                position: Vec2::ZERO,
+                remaining: 0.0,
+            }),
         }
     }
 
@@ -43,29 +57,32 @@ impl Particles {
 
     }
 
-    pub fn create_explosion(&mut self, x: f32, y: f32, size: f32, texture: &Texture2D) {
-        self.explosions.push((
-            Emitter::new(EmitterConfig {
-                amount: size.round() as u32 * 4,
-                texture: Some(texture.clone()),
-                ..Self::get_explosion_emitter()
-            }),
-            vec2(x, y)
-        ));
+    /// Reuses a free emitter, the explosion is skipped when all of them are busy.
+    pub fn create_explosion(&mut self, x: f32, y: f32, size: f32) {
+        if let Some((_, explosion)) = self.explosions.acquire() {
+            explosion.emitter.config.amount = size.round() as u32 * 4;
+            explosion.emitter.config.emitting = true;
+            explosion.position = vec2(x, y);
+            explosion.remaining = EXPLOSION_DURATION;
+        }
     }
 
     pub fn clear(&mut self) {
-        self.explosions = Vec::new();
+        self.explosions.clear();
     }
 
-    pub fn clean(&mut self) {
-        self.explosions.retain(|(explosion, _)| explosion.config.emitting);
+    /// Frees the emitters of the explosions that are over.
+    pub fn update(&mut self, delta_time: f32) {
+        self.explosions.release_where(|explosion| {
+            explosion.remaining -= delta_time;
+            explosion.remaining <= 0.0 && !explosion.emitter.config.emitting
+        });
     }
 
     pub fn draw(&mut self) {
-        for (explosion, coords) in self.explosions.iter_mut() {
-            explosion.draw(*coords);
+        for (_, explosion) in self.explosions.iter_mut() {
+            explosion.emitter.draw(explosion.position);
         }
     }
 
-}
\ No newline at end of file
+}
@@ -0,0 +1,235 @@
+/// Handle to an item of a `Pool`.
+///
+/// The generation changes every time a slot is released, so stale handles
+/// never reach the item that reused their slot.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub struct Handle {
+    index: u32,
+    generation: u32,
+}
+
+impl Handle {
+    pub fn index(&self) -> usize {
+        self.index as usize
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+struct Slot<T> {
+    generation: u32,
+    occupied: bool,
+    value: T,
+}
+
+/// Fixed-capacity pool of reusable items.
+///
+/// Every item is created up front and kept when its slot is released, so
+/// acquiring and releasing never allocate.
+pub struct Pool<T> {
+    slots: Vec<Slot<T>>,
+    free: Vec<u32>,
+}
+
+impl<T: Default> Pool<T> {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::new(capacity, T::default)
+    }
+}
+
+impl<T> Pool<T> {
+    pub fn new<F: FnMut() -> T>(capacity: usize, mut create: F) -> Self {
+        Pool {
+            slots: (0..capacity).map(|_| Slot { generation: 0, occupied: false, value: create() }).collect(),
+            // Reversed so the lowest slots are used first
+            free: (0..capacity as u32).rev().collect(),
+        }
+    }
+
+    /// Takes a free slot, returning its previous item to be reinitialized.
+    pub fn acquire(&mut self) -> Option<(Handle, &mut T)> {
+        let index = self.free.pop()?;
+        let slot = &mut self.slots[index as usize];
+        slot.occupied = true;
+
+        Some((Handle { index, generation: slot.generation }, &mut slot.value))
+    }
+
+    pub fn release(&mut self, handle: Handle) -> bool {
+        if !self.contains(handle) {
+            return false;
+        }
+
+        let slot = &mut self.slots[handle.index()];
+        slot.occupied = false;
+        slot.generation = slot.generation.wrapping_add(1);
+        self.free.push(handle.index);
+
+        true
+    }
+
+    /// Releases every item `should_release` returns `true` for.
+    pub fn release_where<F: FnMut(&mut T) -> bool>(&mut self, mut should_release: F) {
+        // Walked backwards so the lowest released slot is used first
+        for (index, slot) in self.slots.iter_mut().enumerate().rev() {
+            if slot.occupied && should_release(&mut slot.value) {
+                slot.occupied = false;
+                slot.generation = slot.generation.wrapping_add(1);
+                self.free.push(index as u32);
+            }
+        }
+    }
+
+    /// Releases every item. Slots are then acquired in the same order as in
+    /// a new pool, so a cleared world replays the same as a fresh one.
+    pub fn clear(&mut self) {
+        for slot in self.slots.iter_mut().filter(|slot| slot.occupied) {
+            slot.occupied = false;
+            slot.generation = slot.generation.wrapping_add(1);
+        }
+
+        self.free.clear();
+        self.free.extend((0..self.slots.len() as u32).rev());
+    }
+
+    pub fn contains(&self, handle: Handle) -> bool {
+        self.slots
+            .get(handle.index())
+            .is_some_and(|slot| slot.occupied && slot.generation == handle.generation)
+    }
+
+    pub fn get(&self, handle: Handle) -> Option<&T> {
+        self.contains(handle).then(|| &self.slots[handle.index()].value)
+    }
+
+    pub fn get_mut(&mut self, handle: Handle) -> Option<&mut T> {
+        if !self.contains(handle) {
+            return None;
+        }
+
+        Some(&mut self.slots[handle.index()].value)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Handle, &T)> {
+        self.slots.iter().enumerate().filter(|(_, slot)| slot.occupied).map(|(index, slot)| {
+            (Handle { index: index as u32, generation: slot.generation }, &slot.value)
+        })
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Handle, &mut T)> {
+        self.slots.iter_mut().enumerate().filter(|(_, slot)| slot.occupied).map(|(index, slot)| {
+            (Handle { index: index as u32, generation: slot.generation }, &mut slot.value)
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.slots.len() - self.free.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn acquires_lowest_slots_first() {
+        let mut pool: Pool<u32> = Pool::with_capacity(3);
+        let indices: Vec<usize> = (0..3).map(|_| pool.acquire().unwrap().0.index()).collect();
+
+        assert_eq!(indices, [0, 1, 2]);
+        assert!(pool.acquire().is_none());
+    }
+
+    #[test]
+    fn clear_acquires_like_a_new_pool() {
+        let mut pool: Pool<u32> = Pool::with_capacity(4);
+        let handles: Vec<Handle> = (0..4).map(|_| pool.acquire().unwrap().0).collect();
+        pool.release(handles[2]);
+        pool.release(handles[0]);
+        pool.acquire();
+        pool.clear();
+
+        let mut fresh: Pool<u32> = Pool::with_capacity(4);
+        for _ in 0..4 {
+            let cleared = pool.acquire().unwrap().0;
+            let new = fresh.acquire().unwrap().0;
+            assert_eq!(cleared.index(), new.index());
+        }
+        assert_eq!(pool.len(), fresh.len());
+    }
+
+    #[test]
+    fn stale_handles_miss_the_reused_slot() {
+        let mut pool: Pool<u32> = Pool::with_capacity(1);
+        let (stale, value) = pool.acquire().unwrap();
+        *value = 1;
+        assert!(pool.release(stale));
+
+        let (handle, value) = pool.acquire().unwrap();
+        *value = 2;
+
+        assert_eq!(handle.index(), stale.index());
+        assert_ne!(handle, stale);
+        assert!(!pool.contains(stale));
+        assert_eq!(pool.get(stale), None);
+        assert_eq!(pool.get(handle), Some(&2));
+        assert!(!pool.release(stale));
+    }
+
+    #[test]
+    fn stale_handles_miss_after_clear() {
+        let mut pool: Pool<u32> = Pool::with_capacity(2);
+        let stale = pool.acquire().unwrap().0;
+        pool.clear();
+
+        let handle = pool.acquire().unwrap().0;
+        assert_eq!(handle.index(), stale.index());
+        assert!(!pool.contains(stale));
+        assert!(pool.contains(handle));
+    }
+
+    #[test]
+    fn generation_wraps_around() {
+        let mut pool: Pool<u32> = Pool::with_capacity(1);
+        pool.slots[0].generation = u32::MAX;
+
+        let old = pool.acquire().unwrap().0;
+        assert_eq!(old.generation(), u32::MAX);
+        pool.release(old);
+
+        let new = pool.acquire().unwrap().0;
+        assert_eq!(new.generation(), 0);
+        assert!(!pool.contains(old));
+    }
+
+    #[test]
+    fn release_where_releases_matching_items() {
+        let mut pool: Pool<u32> = Pool::with_capacity(4);
+        let handles: Vec<Handle> = (0..4)
+            .map(|value| {
+                let (handle, item) = pool.acquire().unwrap();
+                *item = value;
+                handle
+            })
+            .collect();
+
+        pool.release_where(|value| *value % 2 == 0);
+
+        assert_eq!(pool.len(), 2);
+        assert!(!pool.contains(handles[0]) && !pool.contains(handles[2]));
+        assert!(pool.contains(handles[1]) && pool.contains(handles[3]));
+        // The lowest released slot comes back first
+        assert_eq!(pool.acquire().unwrap().0.index(), 0);
+        assert_eq!(pool.acquire().unwrap().0.index(), 2);
+    }
+}
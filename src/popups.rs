@@ -0,0 +1,137 @@
+use macroquad::{color::{Color, RED}, math::{vec2, Vec2}, text::{draw_text_ex, load_ttf_font, measure_text, Font, TextParams}};
+
+use crate::hud::multiplier_color;
+
+/// Seconds a popup takes to rise and fade.
+const POPUP_DURATION: f32 = 0.9;
+/// Pixels per second a popup rises.
+const RISE_SPEED: f32 = 50.0;
+/// Popups shown at once, the oldest make room for new ones.
+const MAX_POPUPS: usize = 16;
+/// A new popup this close to a young one of the same kind adds to it
+/// instead, so a burst of kills shows one total.
+const BATCH_RADIUS: f32 = 48.0;
+/// Seconds a popup keeps taking in new values.
+const BATCH_WINDOW: f32 = 0.25;
+const FONT_SIZE: u16 = 18;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum PopupKind {
+    Score { multiplier: u32 },
+    Damage,
+}
+
+struct Popup {
+    kind: PopupKind,
+    value: u32,
+    /// Values batched into this popup.
+    count: u32,
+    text: String,
+    position: Vec2,
+    age: f32,
+}
+
+impl Popup {
+    fn update_text(&mut self) {
+        self.text = match self.kind {
+            PopupKind::Score { multiplier: 1 } => format!("+{}", self.value),
+            PopupKind::Score { multiplier } => format!("+{} x{}", self.value, multiplier),
+            PopupKind::Damage => format!("-{}", self.value),
+        };
+    }
+
+    fn color(&self) -> Color {
+        match self.kind {
+            PopupKind::Score { multiplier } => multiplier_color(multiplier),
+            PopupKind::Damage => RED,
+        }
+    }
+}
+
+/// Numbers rising from kills and hits.
+pub struct Popups {
+    popups: Vec<Popup>,
+    font: Font,
+}
+
+impl Popups {
+    pub async fn new() -> Self {
+        Popups {
+            popups: Vec::with_capacity(MAX_POPUPS),
+            font: load_ttf_font("assets/atari_games.ttf").await.unwrap(),
+        }
+    }
+
+    /// Points of a kill, coloured by the multiplier they include.
+    pub fn score(&mut self, position: Vec2, points: u32, multiplier: u32) {
+        self.push(PopupKind::Score { multiplier }, position, points);
+    }
+
+    pub fn damage(&mut self, position: Vec2, damage: u32) {
+        self.push(PopupKind::Damage, position, damage);
+    }
+
+    fn push(&mut self, kind: PopupKind, position: Vec2, value: u32) {
+        let batch = self.popups.iter_mut().find(|popup| {
+            popup.kind == kind && popup.age < BATCH_WINDOW && popup.position.distance(position) < BATCH_RADIUS
+        });
+
+        if let Some(popup) = batch {
+            popup.value += value;
+            popup.count += 1;
+            popup.position = (popup.position + position) / 2.0;
+            popup.age = 0.0;
+            popup.update_text();
+            return;
+        }
+
+        if self.popups.len() == MAX_POPUPS {
+            self.popups.remove(0);
+        }
+
+        let mut popup = Popup {
+            kind,
+            value,
+            count: 1,
+            text: String::new(),
+            position,
+            age: 0.0,
+        };
+        popup.update_text();
+        self.popups.push(popup);
+    }
+
+    pub fn clear(&mut self) {
+        self.popups.clear();
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        for popup in self.popups.iter_mut() {
+            popup.age += delta_time;
+        }
+        self.popups.retain(|popup| popup.age < POPUP_DURATION);
+    }
+
+    pub fn draw(&self) {
+        for popup in self.popups.iter() {
+            // Batched popups grow a little with the values they took in.
+            let font_size = FONT_SIZE + 2 * popup.count.min(5) as u16;
+            let mut color = popup.color();
+            color.a = 1.0 - popup.age / POPUP_DURATION;
+
+            let text_dimensions = measure_text(&popup.text, Some(&self.font), font_size, 1.0);
+            let position = popup.position - vec2(text_dimensions.width / 2.0, RISE_SPEED * popup.age);
+            draw_text_ex(
+                &popup.text,
+                position.x,
+                position.y,
+                TextParams {
+                    font: Some(&self.font),
+                    font_size,
+                    color,
+                    ..Default::default()
+                },
+            );
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use crate::{campaign, constants::INITIAL_LIVES, game_mode::GameMode};
+
+/// Most lives a practice run can start with.
+pub const MAX_PRACTICE_LIVES: u32 = 9;
+/// Speed of the game in slow motion.
+pub const SLOW_MOTION_SCALE: f32 = 0.5;
+
+/// Where a practice run starts and how forgiving it is. Practice runs don't
+/// enter the leaderboards.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Practice {
+    /// Wave the run starts at, in the campaign the level.
+    pub wave: u32,
+    /// In the campaign, whether the run starts at the boss of the level.
+    pub boss: bool,
+    pub lives: u32,
+    /// Hits don't cost lives.
+    pub infinite_lives: bool,
+    pub slow_motion: bool,
+}
+
+impl Default for Practice {
+    fn default() -> Self {
+        Practice {
+            wave: 1,
+            boss: false,
+            lives: INITIAL_LIVES,
+            infinite_lives: false,
+            slow_motion: false,
+        }
+    }
+}
+
+impl Practice {
+    /// Waves, or levels and bosses, a practice run of `mode` can start at
+    /// once the player reached wave `best_wave` and campaign level
+    /// `best_level`. The first wave and level are always there.
+    pub fn starts(mode: GameMode, best_wave: u32, best_level: u32) -> Vec<(u32, bool)> {
+        if !mode.has_levels() {
+            return (1..=best_wave.max(1)).map(|wave| (wave, false)).collect();
+        }
+
+        let levels = campaign::levels();
+        let mut starts = Vec::new();
+        for (index, level) in levels.iter().enumerate().take(best_level.clamp(1, levels.len() as u32) as usize) {
+            let wave = index as u32 + 1;
+            starts.push((wave, false));
+            if level.has_boss() {
+                starts.push((wave, true));
+            }
+        }
+
+        starts
+    }
+
+    /// Name of the start of the run, as in "Wave 3" or "Level 3 boss".
+    pub fn start_name(&self, mode: GameMode) -> String {
+        if !mode.has_levels() {
+            return format!("Wave {}", self.wave);
+        }
+
+        let name = campaign::levels().get(self.wave as usize - 1).map_or("", |level| level.name.as_str());
+        if self.boss {
+            format!("Level {} boss: {}", self.wave, name)
+        } else {
+            format!("Level {}: {}", self.wave, name)
+        }
+    }
+
+    /// Factor of the time of each step.
+    pub fn time_scale(&self) -> f32 {
+        if self.slow_motion { SLOW_MOTION_SCALE } else { 1.0 }
+    }
+
+    /// "wave boss lives infinite_lives slow_motion", the flags as on or off.
+    pub fn to_text(&self) -> String {
+        let on_off = |value: bool| if value { "on" } else { "off" };
+
+        format!("{} {} {} {} {}", self.wave, on_off(self.boss), self.lives, on_off(self.infinite_lives), on_off(self.slow_motion))
+    }
+
+    /// Reads a practice written by `to_text`.
+    pub fn from_text(text: &str) -> Option<Self> {
+        let fields: Vec<&str> = text.split(' ').collect();
+        let [wave, boss, lives, infinite_lives, slow_motion] = fields[..] else {
+            return None;
+        };
+        let flag = |field: &str| match field {
+            "on" => Some(true),
+            "off" => Some(false),
+            _ => None,
+        };
+
+        Some(Practice {
+            wave: wave.parse().ok().filter(|wave| *wave > 0)?,
+            boss: flag(boss)?,
+            lives: lives.parse().ok().filter(|lives| *lives > 0)?,
+            infinite_lives: flag(infinite_lives)?,
+            slow_motion: flag(slow_motion)?,
+        })
+    }
+}
@@ -0,0 +1,231 @@
+use std::{fs, io, path::Path};
+
+use macroquad::math::{vec2, Vec2};
+
+use crate::{constants::REPLAYS_PATH, difficulty::Difficulty, game_mode::GameMode, input::PlayerInput, mutators::Mutators, practice::Practice, run_rules::RunRules, simulation::SIMULATION_VERSION};
+
+const HEADER: &str = "macroquad-tst replay";
+
+fn on_off(value: bool) -> &'static str {
+    if value { "on" } else { "off" }
+}
+
+/// One frame of a recorded run.
+#[derive(Clone, Copy, Debug)]
+pub struct ReplayFrame {
+    pub delta_time: f32,
+    pub input: PlayerInput,
+    /// Size of the play area during the frame.
+    pub area: Vec2,
+    pub pixel_perfect_collisions: bool,
+}
+
+/// State of the run compared when verifying a replay.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Checkpoint {
+    pub score: u32,
+    pub lives: u32,
+    pub wave: u32,
+}
+
+/// Everything needed to play a run again: the seed of its random numbers
+/// and the inputs of every frame.
+#[derive(Default, Clone, Debug)]
+pub struct Replay {
+    /// Version of the game that recorded the run.
+    pub game_version: String,
+    /// `SIMULATION_VERSION` of the game that recorded the run.
+    pub simulation_version: u32,
+    pub seed: u64,
+    /// Size of the play area when the run started.
+    pub area: Vec2,
+    pub pixel_perfect_collisions: bool,
+    pub rules: RunRules,
+    pub frames: Vec<ReplayFrame>,
+    /// Index of the frame after which the state changed, and the new state.
+    pub checkpoints: Vec<(usize, Checkpoint)>,
+}
+
+impl Replay {
+    pub fn new(seed: u64, area: Vec2, pixel_perfect_collisions: bool, rules: RunRules) -> Self {
+        Replay {
+            game_version: env!("CARGO_PKG_VERSION").to_string(),
+            simulation_version: SIMULATION_VERSION,
+            seed,
+            area,
+            pixel_perfect_collisions,
+            rules,
+            frames: Vec::new(),
+            checkpoints: Vec::new(),
+        }
+    }
+
+    /// Adds a frame and the state of the run after it, which is only kept
+    /// when it changed.
+    pub fn record(&mut self, frame: ReplayFrame, checkpoint: Checkpoint) {
+        self.frames.push(frame);
+
+        if self.checkpoints.last().is_none_or(|(_, last)| *last != checkpoint) {
+            self.checkpoints.push((self.frames.len() - 1, checkpoint));
+        }
+    }
+
+    /// Seconds covered by the recorded frames.
+    pub fn duration(&self) -> f32 {
+        self.frames.iter().map(|frame| frame.delta_time).sum()
+    }
+
+    /// Text format, one frame per line. Frame times are written as the bits
+    /// of the float so they are read back exactly. Changes of the play area
+    /// and collision mode are written before the frame they apply to, and
+    /// checkpoints after it.
+    pub fn to_text(&self) -> String {
+        let mut text = format!(
+            "{}\nversion {}\nseed {}\narea {} {}\nprecise {}\nsimulation {}\nmode {}\ndifficulty {}\nmutators {}\n",
+            HEADER,
+            self.game_version,
+            self.seed,
+            self.area.x,
+            self.area.y,
+            on_off(self.pixel_perfect_collisions),
+            self.simulation_version,
+            self.rules.mode.name(),
+            self.rules.difficulty.name(),
+            self.rules.mutators.to_text(),
+        );
+        if let Some(practice) = self.rules.practice {
+            text.push_str(&format!("practice {}\n", practice.to_text()));
+        }
+
+        let (mut area, mut pixel_perfect_collisions) = (self.area, self.pixel_perfect_collisions);
+        let mut checkpoints = self.checkpoints.iter().peekable();
+
+        for (index, frame) in self.frames.iter().enumerate() {
+            if frame.area != area {
+                area = frame.area;
+                text.push_str(&format!("area {} {}\n", area.x, area.y));
+            }
+            if frame.pixel_perfect_collisions != pixel_perfect_collisions {
+                pixel_perfect_collisions = frame.pixel_perfect_collisions;
+                text.push_str(&format!("precise {}\n", on_off(pixel_perfect_collisions)));
+            }
+
+            text.push_str(&format!("{:08x} {}\n", frame.delta_time.to_bits(), frame.input.bits()));
+
+            if let Some((_, checkpoint)) = checkpoints.next_if(|(frame, _)| *frame == index) {
+                text.push_str(&format!("check {} {} {}\n", checkpoint.score, checkpoint.lives, checkpoint.wave));
+            }
+        }
+
+        text
+    }
+
+    pub fn from_text(text: &str) -> Result<Self, String> {
+        let mut lines = text.lines().peekable();
+
+        if lines.next() != Some(HEADER) {
+            return Err("not a replay file".to_string());
+        }
+
+        let mut field = |name: &str| {
+            lines
+                .next()
+                .and_then(|line| line.strip_prefix(name)?.strip_prefix(' '))
+                .ok_or_else(|| format!("missing {}", name))
+        };
+
+        let game_version = field("version")?.to_string();
+        let seed = field("seed")?.parse().map_err(|_| "invalid seed")?;
+        let area = parse_area(field("area")?).ok_or("invalid area")?;
+        let pixel_perfect_collisions = parse_on_off(field("precise")?).ok_or("invalid collision mode")?;
+        // Replays from before these lines have an older simulation version and
+        // are rejected when verified. Their missing rules take the defaults
+        // so they can still be read.
+        let simulation_version = match lines.next_if(|line| line.starts_with("simulation ")) {
+            Some(line) => line["simulation ".len()..].parse().map_err(|_| "invalid simulation version")?,
+            None => 1,
+        };
+        let mut rules = RunRules::default();
+        if let Some(name) = lines.next_if(|line| line.starts_with("mode ")).map(|line| &line["mode ".len()..]) {
+            rules.mode = GameMode::from_name(name).ok_or("invalid mode")?;
+        }
+        if let Some(name) = lines.next_if(|line| line.starts_with("difficulty ")).map(|line| &line["difficulty ".len()..]) {
+            rules.difficulty = Difficulty::from_name(name).ok_or("invalid difficulty")?;
+        }
+        if let Some(text) = lines.next_if(|line| line.starts_with("mutators ")).map(|line| &line["mutators ".len()..]) {
+            rules.mutators = Mutators::from_text(text).ok_or("invalid mutators")?;
+        }
+        if let Some(text) = lines.next_if(|line| line.starts_with("practice ")).map(|line| &line["practice ".len()..]) {
+            rules.practice = Some(Practice::from_text(text).ok_or("invalid practice")?);
+        }
+
+        let mut replay = Replay {
+            game_version,
+            simulation_version,
+            seed,
+            area,
+            pixel_perfect_collisions,
+            rules,
+            frames: Vec::new(),
+            checkpoints: Vec::new(),
+        };
+        let (mut area, mut pixel_perfect_collisions) = (area, pixel_perfect_collisions);
+
+        for line in lines {
+            let invalid = || format!("invalid line after frame {}", replay.frames.len());
+
+            if let Some(value) = line.strip_prefix("area ") {
+                area = parse_area(value).ok_or_else(invalid)?;
+            } else if let Some(value) = line.strip_prefix("precise ") {
+                pixel_perfect_collisions = parse_on_off(value).ok_or_else(invalid)?;
+            } else if let Some(value) = line.strip_prefix("check ") {
+                let checkpoint = parse_checkpoint(value).ok_or_else(invalid)?;
+                let frame = replay.frames.len().checked_sub(1).ok_or_else(invalid)?;
+                replay.checkpoints.push((frame, checkpoint));
+            } else {
+                let (delta_time, input) = line.split_once(' ').ok_or_else(invalid)?;
+                replay.frames.push(ReplayFrame {
+                    delta_time: u32::from_str_radix(delta_time, 16).map(f32::from_bits).map_err(|_| invalid())?,
+                    input: input.parse().map(PlayerInput::from_bits).map_err(|_| invalid())?,
+                    area,
+                    pixel_perfect_collisions,
+                });
+            }
+        }
+
+        Ok(replay)
+    }
+
+    /// Storage key of the replay.
+    pub fn file_name(&self) -> String {
+        format!("{}/replay-{}.txt", REPLAYS_PATH, self.seed)
+    }
+
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let text = fs::read_to_string(path)?;
+        Self::from_text(&text).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+    }
+}
+
+fn parse_area(text: &str) -> Option<Vec2> {
+    let (width, height) = text.split_once(' ')?;
+    Some(vec2(width.parse().ok()?, height.parse().ok()?))
+}
+
+fn parse_on_off(text: &str) -> Option<bool> {
+    match text {
+        "on" => Some(true),
+        "off" => Some(false),
+        _ => None,
+    }
+}
+
+fn parse_checkpoint(text: &str) -> Option<Checkpoint> {
+    let mut values = text.split(' ').map(|value| value.parse().ok());
+
+    Some(Checkpoint {
+        score: values.next()??,
+        lives: values.next()??,
+        wave: values.next()??,
+    })
+}
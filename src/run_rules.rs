@@ -0,0 +1,44 @@
+use crate::{difficulty::Difficulty, game_mode::GameMode, mutators::{Mutator, Mutators}, practice::Practice};
+
+/// Everything chosen before a run that changes how it plays.
+#[derive(PartialEq, Eq, Default, Clone, Copy, Debug)]
+pub struct RunRules {
+    pub mode: GameMode,
+    pub difficulty: Difficulty,
+    pub mutators: Mutators,
+    /// Set for practice runs.
+    pub practice: Option<Practice>,
+}
+
+impl RunRules {
+    pub fn starting_lives(&self) -> u32 {
+        match self.practice {
+            Some(practice) => practice.lives,
+            None => self.mode.starting_lives(self.difficulty),
+        }
+    }
+
+    /// Whether the run can enter the leaderboards.
+    pub fn is_ranked(&self) -> bool {
+        self.practice.is_none()
+    }
+
+    /// Factor of the time of each step.
+    pub fn time_scale(&self) -> f32 {
+        self.practice.map_or(1.0, |practice| practice.time_scale())
+    }
+
+    /// Whether hits cost lives.
+    pub fn loses_lives(&self) -> bool {
+        !self.practice.is_some_and(|practice| practice.infinite_lives)
+    }
+
+    /// Factor of the points of every kill.
+    pub fn score_multiplier(&self) -> f32 {
+        self.difficulty.score_multiplier() * self.mutators.score_multiplier()
+    }
+
+    pub fn has(&self, mutator: Mutator) -> bool {
+        self.mutators.contains(mutator)
+    }
+}
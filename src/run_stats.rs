@@ -0,0 +1,74 @@
+use crate::enemy::EnemySize;
+
+/// What happened during the current run.
+#[derive(Default, Clone, Copy, Debug)]
+pub struct RunStats {
+    /// Seconds played, not counting pauses.
+    pub time_survived: f32,
+    pub shots_fired: u32,
+    /// Shots that hit an enemy.
+    pub shots_hit: u32,
+    /// Indexed by `EnemySize`.
+    pub enemies_destroyed_by_size: [u32; 3],
+    /// Longest chain of kills.
+    pub max_combo: u32,
+    pub lives_lost: u32,
+    /// Pixels travelled by the ship.
+    pub distance_flown: f32,
+    /// Waves the ship went through without being hit.
+    pub no_hit_waves: u32,
+    hit_this_wave: bool,
+}
+
+impl RunStats {
+    pub fn enemy_destroyed(&mut self, size: EnemySize) {
+        self.enemies_destroyed_by_size[size as usize] += 1;
+    }
+
+    pub fn hero_hit(&mut self) {
+        self.lives_lost += 1;
+        self.hit_this_wave = true;
+    }
+
+    pub fn wave_ended(&mut self) {
+        if !self.hit_this_wave {
+            self.no_hit_waves += 1;
+        }
+        self.hit_this_wave = false;
+    }
+
+    pub fn enemies_destroyed(&self) -> u32 {
+        self.enemies_destroyed_by_size.iter().sum()
+    }
+
+    pub fn enemies_destroyed_of(&self, size: EnemySize) -> u32 {
+        self.enemies_destroyed_by_size[size as usize]
+    }
+
+    /// Share of the shots that hit, from 0 to 1.
+    pub fn accuracy(&self) -> f32 {
+        if self.shots_fired == 0 {
+            return 0.0;
+        }
+
+        // Ricocheting bolts can hit twice
+        (self.shots_hit as f32 / self.shots_fired as f32).min(1.0)
+    }
+
+    /// Time survived as "minutes:seconds".
+    pub fn time_text(&self) -> String {
+        let seconds = self.time_survived as u32;
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    }
+
+    /// Letter grade of the run, the score weighted by accuracy.
+    pub fn rank(&self, score: u32) -> char {
+        const RANKS: [(f32, char); 4] = [(3000.0, 'S'), (1500.0, 'A'), (750.0, 'B'), (300.0, 'C')];
+
+        let points = score as f32 * (0.5 + self.accuracy());
+        RANKS
+            .iter()
+            .find(|(threshold, _)| points >= *threshold)
+            .map_or('D', |(_, rank)| *rank)
+    }
+}
@@ -0,0 +1,363 @@
+use std::io;
+
+use crate::{constants::{HIGH_SCORE_FILE_PATH, LEADERBOARDS_FILE_PATH, SAVE_FILE_NAME}, game_settings::GameSettings, leaderboards::Leaderboards, lifetime_stats::LifetimeStats, storage::{self, Storage}};
+
+const HEADER: &str = "macroquad-tst save";
+/// Bumped when the format changes, older files are migrated when read.
+const SAVE_VERSION: u32 = 1;
+
+/// Everything kept between runs.
+#[derive(Default)]
+pub struct SaveData {
+    pub high_score: u32,
+    pub leaderboards: Leaderboards,
+    pub settings: GameSettings,
+    /// Ids of the unlocked achievements.
+    pub achievements: Vec<String>,
+    pub lifetime_stats: LifetimeStats,
+}
+
+impl SaveData {
+    /// Body of the save file, a list of `[section]`s.
+    fn to_text(&self) -> String {
+        format!(
+            "[scores]\nhigh_score {}\n[settings]\n{}[leaderboards]\n{}[achievements]\n{}[stats]\n{}",
+            self.high_score,
+            self.settings.to_text(),
+            self.leaderboards.to_text(),
+            self.achievements.iter().map(|id| format!("{}\n", id)).collect::<String>(),
+            self.lifetime_stats.to_text(),
+        )
+    }
+
+    fn from_text(text: &str) -> Result<Self, String> {
+        let mut data = SaveData::default();
+
+        for (name, body) in sections(text) {
+            match name {
+                "scores" => {
+                    data.high_score = body
+                        .lines()
+                        .find_map(|line| line.strip_prefix("high_score "))
+                        .ok_or("missing high score")?
+                        .parse()
+                        .map_err(|_| "invalid high score")?;
+                }
+                "settings" => data.settings = GameSettings::from_text(body),
+                "leaderboards" => data.leaderboards = Leaderboards::from_text(body),
+                "achievements" => data.achievements = body.lines().map(str::to_string).collect(),
+                "stats" => data.lifetime_stats = LifetimeStats::from_text(body),
+                // Sections of newer versions are ignored
+                _ => {}
+            }
+        }
+
+        Ok(data)
+    }
+}
+
+/// Splits a body into its `[section]`s and their text.
+fn sections(text: &str) -> impl Iterator<Item = (&str, &str)> {
+    text.split("\n[")
+        .map(|section| section.strip_prefix('[').unwrap_or(section))
+        .filter_map(|section| match section.split_once("]\n") {
+            Some(section) => Some(section),
+            None => Some((section.strip_suffix(']')?, "")),
+        })
+}
+
+/// 64 bits FNV-1a hash, catches truncated and hand-edited files.
+fn checksum(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3))
+}
+
+/// Versioned and checksummed file holding the `SaveData`.
+///
+/// Header lines give the format, its version and the checksum of the body.
+pub struct SaveFile {
+    storage: Box<dyn Storage>,
+    /// Where the files written before the save file are migrated from.
+    legacy: Box<dyn Storage>,
+}
+
+impl Default for SaveFile {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SaveFile {
+    pub fn new() -> Self {
+        Self::with_storage(storage::platform_storage(), storage::legacy_storage())
+    }
+
+    fn with_storage(storage: Box<dyn Storage>, legacy: Box<dyn Storage>) -> Self {
+        SaveFile { storage, legacy }
+    }
+
+    /// Reads the saved data. Problems are returned as warnings along with the
+    /// data that could be recovered, so they never stop the game.
+    pub fn load(&mut self) -> (SaveData, Vec<String>) {
+        let mut warnings = Vec::new();
+
+        let text = match self.storage.read(SAVE_FILE_NAME) {
+            Ok(Some(text)) => text,
+            Ok(None) => {
+                let data = self.migrate_legacy_files();
+                // Written right away so the legacy files are only read once
+                if let Err(error) = self.save(&data) {
+                    warnings.push(format!("Could not save: {}", error));
+                }
+                return (data, warnings);
+            }
+            Err(error) => {
+                warnings.push(format!("Could not read the save file: {}", error));
+                return (SaveData::default(), warnings);
+            }
+        };
+
+        match Self::parse(&text) {
+            Ok(data) => (data, warnings),
+            Err(error) => {
+                // Kept aside, the next save would overwrite it
+                let backup = format!("{}.bak", SAVE_FILE_NAME);
+                let location = self.storage.location(&backup);
+                warnings.push(match self.storage.write(&backup, &text) {
+                    Ok(()) => format!("Save file damaged ({}), copied to {}", error, location),
+                    Err(backup_error) => format!("Save file damaged ({}), could not copy it to {}: {}", error, location, backup_error),
+                });
+                (SaveData::default(), warnings)
+            }
+        }
+    }
+
+    fn parse(text: &str) -> Result<SaveData, String> {
+        let mut lines = text.splitn(4, '\n');
+
+        if lines.next() != Some(HEADER) {
+            return Err("not a save file".to_string());
+        }
+
+        let version: u32 = lines
+            .next()
+            .and_then(|line| line.strip_prefix("version "))
+            .and_then(|version| version.parse().ok())
+            .ok_or("missing version")?;
+        if version > SAVE_VERSION {
+            return Err(format!("made by a newer version ({})", version));
+        }
+
+        let expected = lines
+            .next()
+            .and_then(|line| line.strip_prefix("checksum "))
+            .and_then(|checksum| u64::from_str_radix(checksum, 16).ok())
+            .ok_or("missing checksum")?;
+
+        let body = lines.next().unwrap_or("");
+        if checksum(body) != expected {
+            return Err("checksum mismatch".to_string());
+        }
+
+        SaveData::from_text(body)
+    }
+
+    /// Data of the files written before the save file existed.
+    fn migrate_legacy_files(&self) -> SaveData {
+        let read = |key| self.legacy.read(key).ok().flatten();
+
+        SaveData {
+            high_score: read(HIGH_SCORE_FILE_PATH)
+                .and_then(|text| text.trim().parse().ok())
+                .unwrap_or(0),
+            leaderboards: read(LEADERBOARDS_FILE_PATH)
+                .map(|text| Leaderboards::from_text(&text))
+                .unwrap_or_default(),
+            settings: GameSettings::default(),
+            achievements: Vec::new(),
+            lifetime_stats: LifetimeStats::default(),
+        }
+    }
+
+    /// The storage replaces the whole save at once, a failed write never
+    /// leaves a half written save.
+    pub fn save(&mut self, data: &SaveData) -> io::Result<()> {
+        let body = data.to_text();
+        let text = format!("{}\nversion {}\nchecksum {:016x}\n{}", HEADER, SAVE_VERSION, checksum(&body), body);
+
+        self.storage.write(SAVE_FILE_NAME, &text)
+    }
+
+    /// Stores another file next to the save, returns where it was written.
+    pub fn write_file(&mut self, key: &str, text: &str) -> io::Result<String> {
+        self.storage.write(key, text)?;
+        Ok(self.storage.location(key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::{Cell, RefCell}, collections::HashMap, rc::Rc};
+
+    use crate::{difficulty::Difficulty, game_mode::GameMode, leaderboards::{LeaderboardCategory, LeaderboardEntry}};
+
+    use super::*;
+
+    /// Storage in memory, clones share their values.
+    #[derive(Clone, Default)]
+    struct MemoryStorage {
+        values: Rc<RefCell<HashMap<String, String>>>,
+        /// Makes every write fail, as a full disk would.
+        read_only: Rc<Cell<bool>>,
+    }
+
+    impl MemoryStorage {
+        fn get(&self, key: &str) -> Option<String> {
+            self.values.borrow().get(key).cloned()
+        }
+
+        fn set(&self, key: &str, value: &str) {
+            self.values.borrow_mut().insert(key.to_string(), value.to_string());
+        }
+    }
+
+    impl Storage for MemoryStorage {
+        fn read(&self, key: &str) -> io::Result<Option<String>> {
+            Ok(self.get(key))
+        }
+
+        fn write(&mut self, key: &str, value: &str) -> io::Result<()> {
+            if self.read_only.get() {
+                return Err(io::Error::other("read only"));
+            }
+            self.set(key, value);
+            Ok(())
+        }
+
+        fn remove(&mut self, key: &str) -> io::Result<()> {
+            self.values.borrow_mut().remove(key);
+            Ok(())
+        }
+
+        fn location(&self, key: &str) -> String {
+            format!("memory ({})", key)
+        }
+    }
+
+    fn save_file(storage: &MemoryStorage, legacy: &MemoryStorage) -> SaveFile {
+        SaveFile::with_storage(Box::new(storage.clone()), Box::new(legacy.clone()))
+    }
+
+    fn sample_data() -> SaveData {
+        let mut leaderboards = Leaderboards::default();
+        let category = LeaderboardCategory { mode: GameMode::Campaign, difficulty: Difficulty::Hard };
+        let entry = LeaderboardEntry {
+            name: "ABC".to_string(),
+            score: 1234,
+            wave: 3,
+            duration: 95.5,
+            timestamp: 1_760_000_000,
+            seed: 42,
+            ..Default::default()
+        };
+        leaderboards.insert(category, entry);
+
+        SaveData {
+            high_score: 1234,
+            leaderboards,
+            settings: GameSettings { music_enabled: false, debug_overlay: true, ..Default::default() },
+            achievements: vec!["first_blood".to_string(), "survivor".to_string()],
+            lifetime_stats: LifetimeStats { runs: 7, time_played: 321.5, best_wave: 4, ..Default::default() },
+        }
+    }
+
+    #[test]
+    fn saves_round_trip() {
+        let storage = MemoryStorage::default();
+        let data = sample_data();
+        save_file(&storage, &MemoryStorage::default()).save(&data).unwrap();
+
+        let (loaded, warnings) = save_file(&storage, &MemoryStorage::default()).load();
+
+        assert!(warnings.is_empty(), "{:?}", warnings);
+        assert_eq!(loaded.to_text(), data.to_text());
+        assert_eq!(loaded.high_score, 1234);
+        assert_eq!(loaded.achievements, ["first_blood", "survivor"]);
+    }
+
+    #[test]
+    fn damaged_saves_fall_back_to_defaults_and_keep_a_backup() {
+        let storage = MemoryStorage::default();
+        save_file(&storage, &MemoryStorage::default()).save(&sample_data()).unwrap();
+        let damaged = storage.get(SAVE_FILE_NAME).unwrap().replace("high_score 1234", "high_score 9999");
+        storage.set(SAVE_FILE_NAME, &damaged);
+
+        let (loaded, warnings) = save_file(&storage, &MemoryStorage::default()).load();
+
+        assert_eq!(loaded.to_text(), SaveData::default().to_text());
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("checksum mismatch"), "{}", warnings[0]);
+        assert_eq!(storage.get(&format!("{}.bak", SAVE_FILE_NAME)), Some(damaged));
+    }
+
+    #[test]
+    fn failed_backups_are_not_reported_as_made() {
+        let storage = MemoryStorage::default();
+        storage.set(SAVE_FILE_NAME, "damaged");
+        storage.read_only.set(true);
+
+        let (loaded, warnings) = save_file(&storage, &MemoryStorage::default()).load();
+
+        assert_eq!(loaded.to_text(), SaveData::default().to_text());
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("could not copy it") && warnings[0].contains("read only"), "{}", warnings[0]);
+        assert_eq!(storage.get(&format!("{}.bak", SAVE_FILE_NAME)), None);
+    }
+
+    #[test]
+    fn rejects_other_files_and_newer_versions() {
+        let body = SaveData::default().to_text();
+        let newer = format!("{}\nversion {}\nchecksum {:016x}\n{}", HEADER, SAVE_VERSION + 1, checksum(&body), body);
+
+        assert_eq!(SaveFile::parse("1234").err().as_deref(), Some("not a save file"));
+        assert_eq!(SaveFile::parse(&format!("{}\nchecksum 0\n", HEADER)).err().as_deref(), Some("missing version"));
+        assert!(SaveFile::parse(&newer).err().is_some_and(|error| error.contains("newer version")));
+    }
+
+    #[test]
+    fn migrates_legacy_files() {
+        let storage = MemoryStorage::default();
+        let legacy = MemoryStorage::default();
+        legacy.set(HIGH_SCORE_FILE_PATH, "1234\n");
+        legacy.set(LEADERBOARDS_FILE_PATH, "Endless\tNormal\tABC\t1234\t3\t95.5\t1760000000\t42\n");
+
+        let (data, warnings) = save_file(&storage, &legacy).load();
+
+        assert!(warnings.is_empty(), "{:?}", warnings);
+        assert_eq!(data.high_score, 1234);
+        let category = LeaderboardCategory { mode: GameMode::Endless, difficulty: Difficulty::Normal };
+        let entries = data.leaderboards.entries(category);
+        assert_eq!(entries.len(), 1);
+        assert_eq!((entries[0].name.as_str(), entries[0].score, entries[0].seed), ("ABC", 1234, 42));
+
+        // Written right away, the legacy files are not read again
+        assert!(storage.get(SAVE_FILE_NAME).is_some());
+        legacy.set(HIGH_SCORE_FILE_PATH, "1");
+        assert_eq!(save_file(&storage, &legacy).load().0.high_score, 1234);
+    }
+
+    #[test]
+    fn nothing_to_migrate_gives_defaults() {
+        let (data, warnings) = save_file(&MemoryStorage::default(), &MemoryStorage::default()).load();
+
+        assert!(warnings.is_empty());
+        assert_eq!(data.to_text(), SaveData::default().to_text());
+    }
+
+    #[test]
+    fn skips_unknown_sections() {
+        let mut text = sample_data().to_text();
+        text.push_str("[newer]\nsomething 1\n");
+
+        assert_eq!(SaveData::from_text(&text).unwrap().to_text(), sample_data().to_text());
+    }
+}
@@ -0,0 +1,207 @@
+use crate::{game::Game, shaders::DissolveShader, transition::{Transition, TransitionKind}};
+
+/// What the scene stack should do after a scene's update.
+pub enum SceneCommand<G = Game> {
+    None,
+    /// Opens a scene above the current one.
+    Push(Box<dyn Scene<G>>),
+    /// Closes the current scene, going back to the one below.
+    Pop,
+    /// Swaps the current scene for another one.
+    Replace(Box<dyn Scene<G>>),
+    /// Closes every scene and opens a new one.
+    ReplaceAll(Box<dyn Scene<G>>),
+    Quit,
+    /// Covers the screen, applies the command while it is covered and
+    /// reveals the result.
+    Transition(TransitionKind, Box<SceneCommand<G>>),
+}
+
+impl<G> SceneCommand<G> {
+    pub fn with_transition(self, kind: TransitionKind) -> Self {
+        SceneCommand::Transition(kind, Box::new(self))
+    }
+}
+
+/// A screen of the game. Only the top scene is updated, while every scene
+/// under a stack of overlays keeps being drawn, frozen.
+///
+/// `G` is the state shared by the scenes, only changed by tests.
+pub trait Scene<G = Game> {
+    fn enter(&mut self, _game: &mut G) {}
+    fn exit(&mut self, _game: &mut G) {}
+
+    fn update(&mut self, game: &mut G) -> SceneCommand<G>;
+    fn draw(&mut self, _game: &mut G) {}
+
+    /// Builds the menu windows `update` builds, without acting on them, on
+    /// the frames a transition freezes the scene. macroquad only draws the
+    /// windows built during the frame, so they would vanish otherwise.
+    fn frozen_menu(&mut self, _game: &mut G) {}
+
+    /// Overlays are drawn above the scene below them.
+    fn is_overlay(&self) -> bool {
+        false
+    }
+}
+
+pub struct SceneStack<G = Game> {
+    scenes: Vec<Box<dyn Scene<G>>>,
+    transition: Option<Transition<SceneCommand<G>>>,
+    /// Loaded on the first draw.
+    dissolve_shader: Option<DissolveShader>,
+}
+
+impl<G> SceneStack<G> {
+    pub fn new(mut scene: Box<dyn Scene<G>>, game: &mut G) -> Self {
+        scene.enter(game);
+
+        SceneStack {
+            scenes: vec![scene],
+            transition: None,
+            dissolve_shader: None,
+        }
+    }
+
+    fn push(&mut self, mut scene: Box<dyn Scene<G>>, game: &mut G) {
+        scene.enter(game);
+        self.scenes.push(scene);
+    }
+
+    fn pop(&mut self, game: &mut G) {
+        if let Some(mut scene) = self.scenes.pop() {
+            scene.exit(game);
+        }
+    }
+
+    /// Updates the top scene and applies its command, returns `false` once
+    /// the game should quit.
+    ///
+    /// Scenes are frozen while a transition plays, only their menus are
+    /// still built.
+    pub fn update(&mut self, game: &mut G, delta_time: f32) -> bool {
+        if let Some(transition) = self.transition.as_mut() {
+            let covered = transition.update(delta_time);
+            if transition.is_finished() {
+                self.transition = None;
+            }
+
+            let running = match covered {
+                Some(command) => self.apply(command, game),
+                None => !self.scenes.is_empty(),
+            };
+            if let Some(scene) = self.scenes.last_mut() {
+                scene.frozen_menu(game);
+            }
+            return running;
+        }
+
+        let Some(scene) = self.scenes.last_mut() else {
+            return false;
+        };
+
+        let command = scene.update(game);
+        self.apply(command, game)
+    }
+
+    fn apply(&mut self, command: SceneCommand<G>, game: &mut G) -> bool {
+        match command {
+            SceneCommand::None => {}
+            SceneCommand::Push(scene) => self.push(scene, game),
+            SceneCommand::Pop => self.pop(game),
+            SceneCommand::Replace(scene) => {
+                self.pop(game);
+                self.push(scene, game);
+            }
+            SceneCommand::ReplaceAll(scene) => {
+                while !self.scenes.is_empty() {
+                    self.pop(game);
+                }
+                self.push(scene, game);
+            }
+            SceneCommand::Quit => {
+                while !self.scenes.is_empty() {
+                    self.pop(game);
+                }
+            }
+            SceneCommand::Transition(kind, command) => {
+                self.transition = Some(Transition::new(kind, *command));
+            }
+        }
+
+        !self.scenes.is_empty()
+    }
+
+    /// Draws the top scene and, for overlays, the scenes under them.
+    pub fn draw(&mut self, game: &mut G) {
+        let mut first = self.scenes.len().saturating_sub(1);
+        while first > 0 && self.scenes[first].is_overlay() {
+            first -= 1;
+        }
+
+        for scene in self.scenes[first..].iter_mut() {
+            scene.draw(game);
+        }
+
+        if let Some(transition) = self.transition.as_ref() {
+            transition.draw(self.dissolve_shader.get_or_insert_with(DissolveShader::new));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Logs what the stack asks of it, opens `next` on its first update.
+    struct LoggedMenu {
+        name: &'static str,
+        next: Option<&'static str>,
+    }
+
+    impl LoggedMenu {
+        fn new(name: &'static str, next: Option<&'static str>) -> Box<Self> {
+            Box::new(LoggedMenu { name, next })
+        }
+    }
+
+    impl Scene<Vec<String>> for LoggedMenu {
+        fn enter(&mut self, log: &mut Vec<String>) {
+            log.push(format!("{} enter", self.name));
+        }
+
+        fn update(&mut self, log: &mut Vec<String>) -> SceneCommand<Vec<String>> {
+            log.push(format!("{} update", self.name));
+            match self.next.take() {
+                Some(next) => SceneCommand::Push(LoggedMenu::new(next, None)).with_transition(TransitionKind::Fade),
+                None => SceneCommand::None,
+            }
+        }
+
+        fn frozen_menu(&mut self, log: &mut Vec<String>) {
+            log.push(format!("{} frozen", self.name));
+        }
+    }
+
+    #[test]
+    fn menus_keep_being_built_through_a_transition() {
+        let mut log = Vec::new();
+        let mut stack = SceneStack::new(LoggedMenu::new("A", Some("B")), &mut log);
+
+        let mut frames = Vec::new();
+        for _ in 0..6 {
+            log.clear();
+            assert!(stack.update(&mut log, 0.25));
+            frames.push(log.join(", "));
+        }
+
+        assert_eq!(frames, [
+            "A update",
+            "A frozen",
+            "B enter, B frozen",
+            "B frozen",
+            "B frozen",
+            "B update",
+        ]);
+    }
+}
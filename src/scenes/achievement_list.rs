@@ -0,0 +1,62 @@
+use macroquad::{color::{Color, GOLD, GRAY, WHITE}, input::{is_key_pressed, KeyCode}, text::{draw_text, measure_text}, window::{screen_height, screen_width}};
+
+use crate::{game::Game, input::MenuAction, scene::{Scene, SceneCommand}};
+
+/// Achievements shown at once, up and down scroll through the others.
+const VISIBLE_ROWS: usize = 7;
+
+/// Every achievement, the unlocked ones highlighted.
+#[derive(Default)]
+pub struct AchievementList {
+    scroll: usize,
+}
+
+impl AchievementList {
+    fn draw_centered(text: &str, y: f32, font_size: u16, color: Color) {
+        let text_dimensions = measure_text(text, None, font_size, 1.0);
+        draw_text(text, screen_width() / 2.0 - text_dimensions.width / 2.0, y, font_size.into(), color);
+    }
+}
+
+impl Scene for AchievementList {
+    fn update(&mut self, game: &mut Game) -> SceneCommand {
+        let last_scroll = game.achievements.all().len().saturating_sub(VISIBLE_ROWS);
+        if MenuAction::Up.is_pressed(&game.gamepads) {
+            self.scroll = self.scroll.saturating_sub(1);
+        }
+        if MenuAction::Down.is_pressed(&game.gamepads) {
+            self.scroll = (self.scroll + 1).min(last_scroll);
+        }
+
+        if MenuAction::Back.is_pressed(&game.gamepads) || is_key_pressed(KeyCode::B) {
+            return SceneCommand::Pop;
+        }
+
+        SceneCommand::None
+    }
+
+    fn draw(&mut self, game: &mut Game) {
+        const ROW_HEIGHT: f32 = 60.0;
+
+        let achievements = &game.achievements;
+        Self::draw_centered("Achievements", 60.0, 48, GOLD);
+        Self::draw_centered(&format!("{} / {} unlocked", achievements.unlocked_count(), achievements.all().len()), 100.0, 28, WHITE);
+
+        let left = screen_width() / 2.0 - 280.0;
+        let top = 160.0;
+        for (index, achievement) in achievements.all().iter().skip(self.scroll).take(VISIBLE_ROWS).enumerate() {
+            let y = top + ROW_HEIGHT * index as f32;
+            let (mark, color) = if achievements.is_unlocked(&achievement.id) { ("[x]", GOLD) } else { ("[ ]", GRAY) };
+
+            draw_text(mark, left, y, 28.0, color);
+            draw_text(&achievement.name, left + 50.0, y, 28.0, color);
+            draw_text(&achievement.description, left + 50.0, y + 22.0, 20.0, GRAY);
+        }
+
+        if achievements.all().len() > VISIBLE_ROWS {
+            Self::draw_centered("Up/Down: Scroll   (B)ack", screen_height() - 30.0, 24, GRAY);
+        } else {
+            Self::draw_centered("(B)ack", screen_height() - 30.0, 24, GRAY);
+        }
+    }
+}
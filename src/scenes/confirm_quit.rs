@@ -0,0 +1,36 @@
+use macroquad::{input::{is_key_pressed, KeyCode}, ui::hash};
+
+use crate::{game::Game, menu::Menu, scene::{Scene, SceneCommand}};
+
+/// Asks before quitting, so a stray key doesn't end the game.
+pub struct ConfirmQuit;
+
+impl ConfirmQuit {
+    fn menu(interactive: bool) -> Option<usize> {
+        Menu::list_window(hash!(), "Quit game?", &["(Y)es", "(N)o"], 420.0, interactive)
+    }
+}
+
+impl Scene for ConfirmQuit {
+    fn update(&mut self, _game: &mut Game) -> SceneCommand {
+        match Self::menu(true) {
+            Some(0) => return SceneCommand::Quit,
+            Some(_) => return SceneCommand::Pop,
+            None => {}
+        }
+
+        if is_key_pressed(KeyCode::Escape) {
+            return SceneCommand::Pop;
+        }
+
+        SceneCommand::None
+    }
+
+    fn frozen_menu(&mut self, _game: &mut Game) {
+        Self::menu(false);
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,43 @@
+use macroquad::ui::hash;
+
+use crate::{difficulty::Difficulty, game::Game, input::MenuAction, menu::Menu, scene::{Scene, SceneCommand}, scenes::MutatorSelect};
+
+/// Chooses the difficulty of the next run, starting on the last one played.
+#[derive(Default)]
+pub struct DifficultySelect {
+    focus: usize,
+}
+
+impl DifficultySelect {
+    fn menu(&mut self, game: &Game, interactive: bool) -> Option<usize> {
+        let items = ["(E)asy", "(N)ormal", "(H)ard", "(I)nsane", "(B)ack"];
+        Menu::focus_list_window(hash!(), "Difficulty", &items, 420.0, &mut self.focus, &game.gamepads, interactive)
+    }
+}
+
+impl Scene for DifficultySelect {
+    fn enter(&mut self, game: &mut Game) {
+        self.focus = Difficulty::ALL.iter().position(|difficulty| *difficulty == game.rules.difficulty).unwrap_or(0);
+    }
+
+    fn update(&mut self, game: &mut Game) -> SceneCommand {
+        match self.menu(game, true) {
+            Some(index) if index < Difficulty::ALL.len() => {
+                game.rules.difficulty = Difficulty::ALL[index];
+                return SceneCommand::Push(Box::new(MutatorSelect::default()));
+            }
+            Some(_) => return SceneCommand::Pop,
+            None => {}
+        }
+
+        if MenuAction::Back.is_pressed(&game.gamepads) {
+            return SceneCommand::Pop;
+        }
+
+        SceneCommand::None
+    }
+
+    fn frozen_menu(&mut self, game: &mut Game) {
+        self.menu(game, false);
+    }
+}
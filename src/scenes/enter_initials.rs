@@ -0,0 +1,122 @@
+use macroquad::{color::{Color, GOLD, WHITE}, input::{get_char_pressed, is_key_pressed, KeyCode}, shapes::draw_rectangle, text::{draw_text, measure_text}, window::{screen_height, screen_width}};
+
+use crate::{game::Game, input::MenuAction, leaderboards::{LeaderboardCategory, LeaderboardEntry}, scene::{Scene, SceneCommand}};
+
+const INITIALS: usize = 3;
+
+/// Arcade style name entry for a run that made the leaderboard, or that is
+/// submitted online before any initials were entered this session.
+///
+/// Letters are picked with up and down or typed, left and right move between
+/// them.
+pub struct EnterInitials {
+    category: LeaderboardCategory,
+    entry: LeaderboardEntry,
+    /// Whether the run enters the local leaderboard.
+    local: bool,
+    letters: [u8; INITIALS],
+    cursor: usize,
+}
+
+impl EnterInitials {
+    pub fn new(category: LeaderboardCategory, entry: LeaderboardEntry, local: bool) -> Self {
+        EnterInitials {
+            category,
+            entry,
+            local,
+            letters: [b'A'; INITIALS],
+            cursor: 0,
+        }
+    }
+
+    fn save(&mut self, game: &mut Game) {
+        let mut entry = std::mem::take(&mut self.entry);
+        entry.name = self.letters.iter().map(|letter| *letter as char).collect();
+        game.initials = Some(entry.name.clone());
+
+        game.online.submit(self.category, &entry, &game.replay, &mut game.toasts);
+        if self.local {
+            game.leaderboards.insert(self.category, entry);
+            game.save();
+        }
+    }
+}
+
+impl Scene for EnterInitials {
+    fn enter(&mut self, game: &mut Game) {
+        // Drops the characters typed while playing
+        while get_char_pressed().is_some() {}
+
+        if let Some(initials) = game.initials.as_ref() {
+            for (letter, initial) in self.letters.iter_mut().zip(initials.bytes()) {
+                *letter = initial;
+            }
+        }
+    }
+
+    fn update(&mut self, game: &mut Game) -> SceneCommand {
+        let letter = &mut self.letters[self.cursor];
+
+        if MenuAction::Up.is_pressed(&game.gamepads) {
+            *letter = if *letter == b'Z' { b'A' } else { *letter + 1 };
+        }
+        if MenuAction::Down.is_pressed(&game.gamepads) {
+            *letter = if *letter == b'A' { b'Z' } else { *letter - 1 };
+        }
+        if MenuAction::Left.is_pressed(&game.gamepads) || is_key_pressed(KeyCode::Backspace) {
+            self.cursor = self.cursor.saturating_sub(1);
+        }
+        if MenuAction::Right.is_pressed(&game.gamepads) {
+            self.cursor = (self.cursor + 1).min(INITIALS - 1);
+        }
+
+        while let Some(character) = get_char_pressed() {
+            if character.is_ascii_alphabetic() {
+                self.letters[self.cursor] = character.to_ascii_uppercase() as u8;
+                self.cursor = (self.cursor + 1).min(INITIALS - 1);
+            }
+        }
+
+        if MenuAction::Confirm.is_pressed(&game.gamepads) {
+            self.save(game);
+            return SceneCommand::Pop;
+        }
+
+        SceneCommand::None
+    }
+
+    fn draw(&mut self, _game: &mut Game) {
+        const FONT_SIZE: u16 = 100;
+        const TITLE_FONT_SIZE: u16 = 40;
+
+        draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::new(0.0, 0.0, 0.0, 0.8));
+
+        let title = if self.local { "TOP 10! Enter your initials" } else { "Enter your initials for the global board" };
+        let title_dimensions = measure_text(title, None, TITLE_FONT_SIZE, 1.0);
+        draw_text(
+            title,
+            screen_width() / 2.0 - title_dimensions.width / 2.0,
+            screen_height() / 3.0,
+            TITLE_FONT_SIZE.into(),
+            GOLD,
+        );
+
+        let letter_width = measure_text("W", None, FONT_SIZE, 1.0).width * 1.5;
+        let left = screen_width() / 2.0 - letter_width * INITIALS as f32 / 2.0;
+        let baseline = screen_height() / 2.0 + FONT_SIZE as f32 / 2.0;
+
+        for (index, letter) in self.letters.iter().enumerate() {
+            let x = left + letter_width * index as f32;
+            let color = if index == self.cursor { GOLD } else { WHITE };
+
+            draw_text(&(*letter as char).to_string(), x, baseline, FONT_SIZE.into(), color);
+            if index == self.cursor {
+                draw_rectangle(x, baseline + 10.0, letter_width * 0.7, 6.0, GOLD);
+            }
+        }
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
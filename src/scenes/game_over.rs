@@ -0,0 +1,150 @@
+use macroquad::{color::{Color, GOLD, RED, WHITE}, shapes::draw_rectangle, text::{draw_text, measure_text}, ui::hash, window::{screen_height, screen_width}};
+
+use crate::{enemy::EnemySize, game::Game, leaderboards::LeaderboardEntry, online_leaderboard::OnlineLeaderboard, simulation::RunOutcome, input::MenuAction, menu::Menu, scene::{Scene, SceneCommand}, scenes::{EnterInitials, MainMenu, Playing}, transition::TransitionKind};
+
+/// Results of the run, shown above its last frame.
+#[derive(Default)]
+pub struct GameOver {
+    /// Best score of the leaderboard category of the run, before this run.
+    previous_high_score: u32,
+    focus: usize,
+    /// Outcome of the last "Save replay".
+    replay_message: Option<String>,
+    /// Whether the run was checked against the leaderboard.
+    leaderboard_checked: bool,
+}
+
+impl GameOver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn high_score_line(&self, score: u32) -> String {
+        if score > self.previous_high_score {
+            format!("New high score! (was {})", self.previous_high_score)
+        } else {
+            format!("High score: {} ({} to go)", self.previous_high_score, self.previous_high_score - score)
+        }
+    }
+
+    fn draw_results(&self, game: &Game) {
+        const FONT_SIZE: f32 = 22.0;
+
+        let stats = &game.simulation.stats;
+        let score = game.simulation.score;
+        let (category, high_score_line) = match game.simulation.rules.practice {
+            Some(practice) => ("Practice".to_string(), practice.start_name(game.simulation.rules.mode)),
+            None => (game.leaderboard_category().name(), self.high_score_line(score)),
+        };
+        let lines = [
+            format!("Score: {} ({})", score, category),
+            high_score_line,
+            String::new(),
+            format!("Small: {}", stats.enemies_destroyed_of(EnemySize::Small)),
+            format!("Medium: {}", stats.enemies_destroyed_of(EnemySize::Medium)),
+            format!("Big: {}", stats.enemies_destroyed_of(EnemySize::Big)),
+            String::new(),
+            format!("Shots: {}", stats.shots_fired),
+            format!("Accuracy: {:.0}%", stats.accuracy() * 100.0),
+            format!("Time: {}", stats.time_text()),
+            format!("Max combo: {}", stats.max_combo),
+        ];
+
+        let top = screen_height() / 2.0 - FONT_SIZE * lines.len() as f32 / 2.0;
+        for (index, line) in lines.iter().enumerate() {
+            draw_text(line, 20.0, top + FONT_SIZE * index as f32, FONT_SIZE, WHITE);
+        }
+
+        if let Some(message) = self.replay_message.as_ref() {
+            draw_text(message, 20.0, screen_height() - FONT_SIZE, FONT_SIZE, WHITE);
+        }
+    }
+
+    fn draw_rank(game: &Game) {
+        const FONT_SIZE: u16 = 160;
+
+        let rank = game.simulation.stats.rank(game.simulation.score).to_string();
+        let text_dimensions = measure_text(&rank, None, FONT_SIZE, 1.0);
+        let x = screen_width() - text_dimensions.width - 40.0;
+        let y = screen_height() / 2.0 + text_dimensions.height / 2.0;
+
+        draw_text("Rank", x, y - text_dimensions.height - 10.0, 28.0, WHITE);
+        draw_text(&rank, x, y, FONT_SIZE.into(), if rank == "S" { GOLD } else { RED });
+    }
+
+    fn menu(&mut self, game: &Game, interactive: bool) -> Option<usize> {
+        let title = match game.simulation.outcome() {
+            Some(RunOutcome::TimeUp) => "Time's Up",
+            Some(RunOutcome::Completed) => "Victory!",
+            _ => "Game Over",
+        };
+        let items = ["(R)etry", "(M)enu", "(S)ave"];
+        Menu::focus_list_window(hash!(), title, &items, 420.0, &mut self.focus, &game.gamepads, interactive)
+    }
+}
+
+impl Scene for GameOver {
+    fn enter(&mut self, game: &mut Game) {
+        let best = game.leaderboards.entries(game.leaderboard_category()).first();
+        self.previous_high_score = best.map_or(0, |entry| entry.score);
+        if game.simulation.rules.is_ranked() && game.scores.check_score_vs_high_score(game.simulation.score) {
+            game.save();
+        }
+    }
+
+    fn update(&mut self, game: &mut Game) -> SceneCommand {
+        // Practice runs are never ranked
+        if !self.leaderboard_checked && game.simulation.rules.is_ranked() {
+            self.leaderboard_checked = true;
+
+            let category = game.leaderboard_category();
+            let local = game.leaderboards.qualifies(category, game.simulation.score);
+            let online = OnlineLeaderboard::is_supported() && game.simulation.score > 0;
+            match game.initials.clone() {
+                // Every ranked run goes online, the initials are only asked
+                // for once a session unless the run makes the local board
+                Some(name) if online && !local => {
+                    let entry = LeaderboardEntry { name, ..game.leaderboard_entry() };
+                    game.online.submit(category, &entry, &game.replay, &mut game.toasts);
+                }
+                _ if local || online => {
+                    return SceneCommand::Push(Box::new(EnterInitials::new(category, game.leaderboard_entry(), local)));
+                }
+                _ => {}
+            }
+        }
+
+        match self.menu(game, true) {
+            Some(0) => return SceneCommand::ReplaceAll(Box::new(Playing)).with_transition(TransitionKind::ShaderDissolve),
+            Some(1) => return SceneCommand::ReplaceAll(Box::new(MainMenu)).with_transition(TransitionKind::Wipe),
+            Some(_) => {
+                self.replay_message = Some(match game.save_replay() {
+                    Ok(location) => format!("Replay saved to {}", location),
+                    Err(error) => format!("Could not save the replay: {}", error),
+                });
+            }
+            None => {}
+        }
+
+        if MenuAction::Back.is_pressed(&game.gamepads) {
+            return SceneCommand::ReplaceAll(Box::new(MainMenu)).with_transition(TransitionKind::Wipe);
+        }
+
+        SceneCommand::None
+    }
+
+    fn frozen_menu(&mut self, game: &mut Game) {
+        self.menu(game, false);
+    }
+
+    fn draw(&mut self, game: &mut Game) {
+        draw_rectangle(0.0, 0.0, screen_width(), screen_height(), Color::new(0.0, 0.0, 0.0, 0.6));
+
+        self.draw_results(game);
+        Self::draw_rank(game);
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,129 @@
+use macroquad::{color::{Color, GOLD, GRAY, WHITE}, input::{is_key_pressed, KeyCode}, text::{draw_text, measure_text}, window::{screen_height, screen_width}};
+
+use crate::{game::Game, input::MenuAction, leaderboards::{LeaderboardCategory, LeaderboardEntry, LEADERBOARD_SIZE}, online_leaderboard::{OnlineLeaderboard, OnlineScores}, scene::{Scene, SceneCommand}};
+
+/// Top runs of each mode and difficulty, left and right switch between them
+/// and G between the local and the online leaderboards.
+pub struct Leaderboard {
+    categories: Vec<LeaderboardCategory>,
+    selected: usize,
+    global: bool,
+}
+
+impl Default for Leaderboard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Leaderboard {
+    pub fn new() -> Self {
+        Leaderboard {
+            categories: LeaderboardCategory::all().collect(),
+            selected: 0,
+            global: false,
+        }
+    }
+
+    fn draw_centered(text: &str, y: f32, font_size: u16, color: Color) {
+        let text_dimensions = measure_text(text, None, font_size, 1.0);
+        draw_text(text, screen_width() / 2.0 - text_dimensions.width / 2.0, y, font_size.into(), color);
+    }
+}
+
+impl Scene for Leaderboard {
+    fn enter(&mut self, game: &mut Game) {
+        let current = game.leaderboard_category();
+        self.selected = self.categories.iter().position(|category| *category == current).unwrap_or(0);
+    }
+
+    fn update(&mut self, game: &mut Game) -> SceneCommand {
+        let count = self.categories.len();
+        if MenuAction::Left.is_pressed(&game.gamepads) {
+            self.selected = (self.selected + count - 1) % count;
+        }
+        if MenuAction::Right.is_pressed(&game.gamepads) {
+            self.selected = (self.selected + 1) % count;
+        }
+
+        if is_key_pressed(KeyCode::G) {
+            self.global = !self.global;
+        }
+        if self.global && OnlineLeaderboard::is_supported() {
+            game.online.scores(self.categories[self.selected], is_key_pressed(KeyCode::R));
+        }
+
+        if MenuAction::Back.is_pressed(&game.gamepads) || is_key_pressed(KeyCode::B) {
+            return SceneCommand::Pop;
+        }
+
+        SceneCommand::None
+    }
+
+    fn draw(&mut self, game: &mut Game) {
+        const FONT_SIZE: u16 = 24;
+        const ROW_HEIGHT: f32 = 30.0;
+        const COLUMNS: [f32; 6] = [0.0, 50.0, 130.0, 260.0, 340.0, 430.0];
+        const HEADERS: [&str; 6] = ["#", "NAME", "SCORE", "WAVE", "TIME", "DATE"];
+
+        let category = self.categories[self.selected];
+        let pending = game.online.pending();
+        let (entries, status): (&[LeaderboardEntry], Option<String>) = if self.global && !OnlineLeaderboard::is_supported() {
+            (&[], Some("Online leaderboard unavailable in the web build".to_string()))
+        } else if self.global {
+            match game.online.scores(category, false) {
+                OnlineScores::Loading => (&[], Some("Loading...".to_string())),
+                OnlineScores::Loaded(entries) => (entries, None),
+                OnlineScores::Failed(error) => (&[], Some(format!("Offline: {}", error))),
+            }
+        } else {
+            (game.leaderboards.entries(category), None)
+        };
+
+        Self::draw_centered(if self.global { "Global Leaderboard" } else { "Leaderboard" }, 60.0, 48, GOLD);
+        Self::draw_centered(&format!("< {} >", category.name()), 100.0, 28, WHITE);
+
+        let left = screen_width() / 2.0 - 280.0;
+        let top = 150.0;
+        for (header, column) in HEADERS.iter().zip(COLUMNS) {
+            draw_text(header, left + column, top, FONT_SIZE.into(), GRAY);
+        }
+
+        for index in 0..LEADERBOARD_SIZE {
+            let y = top + ROW_HEIGHT * (index + 1) as f32;
+            let Some(entry) = entries.get(index) else {
+                draw_text(&format!("{}", index + 1), left, y, FONT_SIZE.into(), GRAY);
+                draw_text("---", left + COLUMNS[1], y, FONT_SIZE.into(), GRAY);
+                continue;
+            };
+
+            let seconds = entry.duration as u32;
+            // Runs with mutators are starred, their rules are listed below
+            let mutated = if entry.mutators.is_empty() { "" } else { "*" };
+            let cells = [
+                format!("{}", index + 1),
+                entry.name.clone(),
+                format!("{}{}", entry.score, mutated),
+                entry.wave.to_string(),
+                format!("{}:{:02}", seconds / 60, seconds % 60),
+                entry.date_text(),
+            ];
+            for (cell, column) in cells.iter().zip(COLUMNS) {
+                draw_text(cell, left + column, y, FONT_SIZE.into(), WHITE);
+            }
+        }
+
+        if entries.iter().any(|entry| !entry.mutators.is_empty()) {
+            Self::draw_centered("* played with mutators", top + ROW_HEIGHT * (LEADERBOARD_SIZE + 1) as f32 + 10.0, 20, GRAY);
+        }
+        if let Some(status) = status {
+            Self::draw_centered(&status, top + ROW_HEIGHT * (LEADERBOARD_SIZE + 2) as f32, FONT_SIZE, GRAY);
+        }
+        if pending > 0 {
+            Self::draw_centered(&format!("{} scores waiting to be sent", pending), screen_height() - 60.0, FONT_SIZE, GRAY);
+        }
+
+        let help = if self.global { "(G)lobal: On   (R)efresh   (B)ack" } else { "(G)lobal: Off   (B)ack" };
+        Self::draw_centered(help, screen_height() - 30.0, FONT_SIZE, GRAY);
+    }
+}
@@ -0,0 +1,20 @@
+use crate::{game::Game, menu::{MainMenuItem, Menu}, scene::{Scene, SceneCommand}, scenes::{ModeSelect, PracticeSelect, Records, Settings}, transition::TransitionKind};
+
+pub struct MainMenu;
+
+impl Scene for MainMenu {
+    fn update(&mut self, _game: &mut Game) -> SceneCommand {
+        match Menu::main_menu(true) {
+            Some(MainMenuItem::Play) => SceneCommand::Push(Box::new(ModeSelect::default())).with_transition(TransitionKind::Fade),
+            Some(MainMenuItem::Practice) => SceneCommand::Push(Box::new(PracticeSelect::default())).with_transition(TransitionKind::Fade),
+            Some(MainMenuItem::Settings) => SceneCommand::Push(Box::new(Settings)).with_transition(TransitionKind::PixelDissolve),
+            Some(MainMenuItem::Records) => SceneCommand::Push(Box::new(Records::default())).with_transition(TransitionKind::Fade),
+            Some(MainMenuItem::Quit) => SceneCommand::Quit,
+            None => SceneCommand::None,
+        }
+    }
+
+    fn frozen_menu(&mut self, _game: &mut Game) {
+        Menu::main_menu(false);
+    }
+}
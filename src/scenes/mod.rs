@@ -0,0 +1,31 @@
+pub mod main_menu;
+pub mod playing;
+pub mod paused;
+pub mod game_over;
+pub mod settings;
+pub mod leaderboard;
+pub mod confirm_quit;
+pub mod enter_initials;
+pub mod achievement_list;
+pub mod records;
+pub mod statistics;
+pub mod difficulty_select;
+pub mod mode_select;
+pub mod mutator_select;
+pub mod practice_select;
+
+pub use main_menu::MainMenu;
+pub use playing::Playing;
+pub use paused::Paused;
+pub use game_over::GameOver;
+pub use settings::Settings;
+pub use leaderboard::Leaderboard;
+pub use confirm_quit::ConfirmQuit;
+pub use enter_initials::EnterInitials;
+pub use achievement_list::AchievementList;
+pub use records::Records;
+pub use statistics::Statistics;
+pub use difficulty_select::DifficultySelect;
+pub use mode_select::ModeSelect;
+pub use mutator_select::MutatorSelect;
+pub use practice_select::PracticeSelect;
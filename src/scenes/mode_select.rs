@@ -0,0 +1,55 @@
+use macroquad::{color::{Color, GRAY}, text::{draw_text, measure_text}, ui::hash, window::{screen_height, screen_width}};
+
+use crate::{game::Game, game_mode::GameMode, input::MenuAction, menu::Menu, scene::{Scene, SceneCommand}, scenes::DifficultySelect};
+
+/// Chooses the mode of the next run, then its difficulty.
+#[derive(Default)]
+pub struct ModeSelect {
+    focus: usize,
+}
+
+impl ModeSelect {
+    fn menu(&mut self, game: &Game, interactive: bool) -> Option<usize> {
+        let items = ["(E)ndless", "(C)ampaign", "(T)ime Attack", "(S)urvival", "(B)ack"];
+        Menu::focus_list_window(hash!(), "Mode", &items, 520.0, &mut self.focus, &game.gamepads, interactive)
+    }
+
+    fn draw_centered(text: &str, y: f32, font_size: u16, color: Color) {
+        let text_dimensions = measure_text(text, None, font_size, 1.0);
+        draw_text(text, screen_width() / 2.0 - text_dimensions.width / 2.0, y, font_size.into(), color);
+    }
+}
+
+impl Scene for ModeSelect {
+    fn enter(&mut self, game: &mut Game) {
+        self.focus = GameMode::ALL.iter().position(|mode| *mode == game.rules.mode).unwrap_or(0);
+    }
+
+    fn update(&mut self, game: &mut Game) -> SceneCommand {
+        match self.menu(game, true) {
+            Some(index) if index < GameMode::ALL.len() => {
+                game.rules.mode = GameMode::ALL[index];
+                game.rules.practice = None;
+                return SceneCommand::Push(Box::new(DifficultySelect::default()));
+            }
+            Some(_) => return SceneCommand::Pop,
+            None => {}
+        }
+
+        if MenuAction::Back.is_pressed(&game.gamepads) {
+            return SceneCommand::Pop;
+        }
+
+        SceneCommand::None
+    }
+
+    fn frozen_menu(&mut self, game: &mut Game) {
+        self.menu(game, false);
+    }
+
+    fn draw(&mut self, _game: &mut Game) {
+        if let Some(mode) = GameMode::ALL.get(self.focus) {
+            Self::draw_centered(mode.description(), screen_height() - 20.0, 24, GRAY);
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use macroquad::{color::{Color, GOLD, GRAY, GREEN, WHITE}, input::{is_key_pressed, KeyCode}, text::{draw_text, measure_text}, window::{screen_height, screen_width}};
+
+use crate::{game::Game, input::MenuAction, mutators::Mutator, scene::{Scene, SceneCommand}, scenes::Playing, transition::TransitionKind};
+
+/// Toggles the mutators of the next run, keeping the ones of the last run,
+/// then starts it.
+#[derive(Default)]
+pub struct MutatorSelect {
+    /// Focused row, the one after the mutators is "Start".
+    focus: usize,
+}
+
+impl MutatorSelect {
+    fn draw_centered(text: &str, y: f32, font_size: u16, color: Color) {
+        let text_dimensions = measure_text(text, None, font_size, 1.0);
+        draw_text(text, screen_width() / 2.0 - text_dimensions.width / 2.0, y, font_size.into(), color);
+    }
+
+    fn start() -> SceneCommand {
+        SceneCommand::ReplaceAll(Box::new(Playing)).with_transition(TransitionKind::Iris)
+    }
+}
+
+impl Scene for MutatorSelect {
+    fn update(&mut self, game: &mut Game) -> SceneCommand {
+        let count = Mutator::ALL.len() + 1;
+        if MenuAction::Up.is_pressed(&game.gamepads) {
+            self.focus = (self.focus + count - 1) % count;
+        }
+        if MenuAction::Down.is_pressed(&game.gamepads) {
+            self.focus = (self.focus + 1) % count;
+        }
+
+        if MenuAction::Confirm.is_pressed(&game.gamepads) || is_key_pressed(KeyCode::Space) {
+            match Mutator::ALL.get(self.focus) {
+                Some(mutator) => game.rules.mutators.toggle(*mutator),
+                None => return Self::start(),
+            }
+        }
+        if is_key_pressed(KeyCode::S) {
+            return Self::start();
+        }
+
+        if MenuAction::Back.is_pressed(&game.gamepads) || is_key_pressed(KeyCode::B) {
+            return SceneCommand::Pop;
+        }
+
+        SceneCommand::None
+    }
+
+    fn draw(&mut self, game: &mut Game) {
+        const FONT_SIZE: u16 = 24;
+        const ROW_HEIGHT: f32 = 30.0;
+        const COLUMNS: [f32; 3] = [0.0, 50.0, 420.0];
+
+        Self::draw_centered("Mutators", 60.0, 48, GOLD);
+
+        let left = screen_width() / 2.0 - 240.0;
+        let top = 120.0;
+        for (index, mutator) in Mutator::ALL.iter().enumerate() {
+            let y = top + ROW_HEIGHT * index as f32;
+            let enabled = game.rules.mutators.contains(*mutator);
+            let color = if index == self.focus { GOLD } else if enabled { WHITE } else { GRAY };
+
+            let cells = [
+                if enabled { "[x]" } else { "[ ]" }.to_string(),
+                mutator.name().to_string(),
+                format!("x{:.1}", mutator.score_multiplier()),
+            ];
+            for (cell, column) in cells.iter().zip(COLUMNS) {
+                draw_text(cell, left + column, y, FONT_SIZE.into(), color);
+            }
+        }
+
+        let start_y = top + ROW_HEIGHT * (Mutator::ALL.len() + 1) as f32;
+        let start_color = if self.focus == Mutator::ALL.len() { GOLD } else { WHITE };
+        draw_text("Start", left + COLUMNS[1], start_y, FONT_SIZE.into(), start_color);
+
+        let multiplier = game.rules.score_multiplier();
+        Self::draw_centered(&format!("Score multiplier: x{:.2}", multiplier), start_y + ROW_HEIGHT * 2.0, FONT_SIZE, if multiplier >= 1.0 { GREEN } else { WHITE });
+        if let Some(mutator) = Mutator::ALL.get(self.focus) {
+            Self::draw_centered(mutator.description(), screen_height() - 60.0, FONT_SIZE, GRAY);
+        }
+        Self::draw_centered("(Enter) Toggle   (S)tart   (B)ack", screen_height() - 30.0, FONT_SIZE, GRAY);
+    }
+}
@@ -0,0 +1,68 @@
+use macroquad::{color::WHITE, text::draw_text, ui::hash, window::screen_height};
+
+use crate::{game::Game, input::MenuAction, menu::Menu, scene::{Scene, SceneCommand}, scenes::{ConfirmQuit, MainMenu, Playing, Settings}, transition::TransitionKind};
+
+/// Pause menu, shown above the frozen run.
+#[derive(Default)]
+pub struct Paused {
+    focus: usize,
+}
+
+impl Paused {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn menu(&mut self, game: &Game, interactive: bool) -> Option<usize> {
+        let items = ["(R)esume", "Res(t)art", "(S)ettings", "(M)ain Menu", "(Q)uit"];
+        Menu::focus_list_window(hash!(), "Paused", &items, 420.0, &mut self.focus, &game.gamepads, interactive)
+    }
+
+    fn draw_run_stats(game: &Game) {
+        const FONT_SIZE: f32 = 25.0;
+
+        let lines = [
+            format!("Score: {}", game.simulation.score),
+            format!("Lives: {}", game.simulation.lives),
+            format!("Time: {}", game.simulation.stats.time_text()),
+            format!("Kills: {}", game.simulation.stats.enemies_destroyed()),
+            format!("Shots: {}", game.simulation.stats.shots_fired),
+        ];
+        let top = screen_height() / 2.0 - FONT_SIZE * lines.len() as f32 / 2.0;
+
+        for (index, line) in lines.iter().enumerate() {
+            draw_text(line, 20.0, top + FONT_SIZE * index as f32, FONT_SIZE, WHITE);
+        }
+    }
+}
+
+impl Scene for Paused {
+    fn update(&mut self, game: &mut Game) -> SceneCommand {
+        match self.menu(game, true) {
+            Some(0) => return SceneCommand::Pop,
+            Some(1) => return SceneCommand::ReplaceAll(Box::new(Playing)).with_transition(TransitionKind::Fade),
+            Some(2) => return SceneCommand::Push(Box::new(Settings)),
+            Some(3) => return SceneCommand::ReplaceAll(Box::new(MainMenu)).with_transition(TransitionKind::Wipe),
+            Some(_) => return SceneCommand::Push(Box::new(ConfirmQuit)),
+            None => {}
+        }
+
+        if MenuAction::Back.is_pressed(&game.gamepads) {
+            return SceneCommand::Pop;
+        }
+
+        SceneCommand::None
+    }
+
+    fn frozen_menu(&mut self, game: &mut Game) {
+        self.menu(game, false);
+    }
+
+    fn draw(&mut self, game: &mut Game) {
+        Self::draw_run_stats(game);
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
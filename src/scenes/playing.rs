@@ -0,0 +1,33 @@
+use macroquad::input::{is_key_pressed, KeyCode};
+
+use crate::{game::Game, gamepad::GamepadButton, scene::{Scene, SceneCommand}, scenes::{GameOver, Paused}};
+
+pub struct Playing;
+
+impl Scene for Playing {
+    fn enter(&mut self, game: &mut Game) {
+        game.restart();
+    }
+
+    fn exit(&mut self, game: &mut Game) {
+        game.end_run();
+    }
+
+    fn update(&mut self, game: &mut Game) -> SceneCommand {
+        game.update();
+
+        if game.is_game_over() {
+            return SceneCommand::Push(Box::new(GameOver::new()));
+        }
+
+        if is_key_pressed(KeyCode::Escape) || game.gamepads.is_pressed(GamepadButton::Start) {
+            return SceneCommand::Push(Box::new(Paused::new()));
+        }
+
+        SceneCommand::None
+    }
+
+    fn draw(&mut self, game: &mut Game) {
+        game.draw();
+    }
+}
@@ -0,0 +1,149 @@
+use macroquad::{color::{Color, GOLD, GRAY, WHITE}, input::{is_key_pressed, KeyCode}, text::{draw_text, measure_text}, window::{screen_height, screen_width}};
+
+use crate::{difficulty::Difficulty, game::Game, game_mode::GameMode, input::MenuAction, practice::{Practice, MAX_PRACTICE_LIVES}, scene::{Scene, SceneCommand}, scenes::{MutatorSelect, Playing}, transition::TransitionKind};
+
+const MODE: usize = 0;
+const DIFFICULTY: usize = 1;
+const START: usize = 2;
+const LIVES: usize = 3;
+const INFINITE_LIVES: usize = 4;
+const SLOW_MOTION: usize = 5;
+const MUTATORS: usize = 6;
+const PLAY: usize = 7;
+const ROWS: usize = 8;
+
+/// Sets up an unranked run from any unlocked wave, campaign level or boss.
+/// Left and right change the focused setting. Besides the lives, the run is
+/// only set up through its mutators, the ship and its weapon are the usual ones.
+#[derive(Default)]
+pub struct PracticeSelect {
+    focus: usize,
+}
+
+impl PracticeSelect {
+    fn draw_centered(text: &str, y: f32, font_size: u16, color: Color) {
+        let text_dimensions = measure_text(text, None, font_size, 1.0);
+        draw_text(text, screen_width() / 2.0 - text_dimensions.width / 2.0, y, font_size.into(), color);
+    }
+
+    /// Moves `value`, an index in a list of `count` items, by `step`
+    /// wrapping around.
+    fn cycle(value: usize, count: usize, step: isize) -> usize {
+        (value as isize + step).rem_euclid(count as isize) as usize
+    }
+
+    fn starts(game: &Game) -> Vec<(u32, bool)> {
+        Practice::starts(game.rules.mode, game.lifetime_stats.best_wave, game.lifetime_stats.best_level)
+    }
+
+    /// Changes the focused setting by `step`, toggles ignore its sign.
+    fn change(&self, game: &mut Game, step: isize) {
+        let starts = Self::starts(game);
+        let rules = &mut game.rules;
+        let practice = rules.practice.get_or_insert_default();
+
+        match self.focus {
+            MODE => {
+                let index = GameMode::ALL.iter().position(|mode| *mode == rules.mode).unwrap_or(0);
+                rules.mode = GameMode::ALL[Self::cycle(index, GameMode::ALL.len(), step)];
+                *practice = Practice { wave: 1, boss: false, ..*practice };
+            }
+            DIFFICULTY => {
+                let index = Difficulty::ALL.iter().position(|difficulty| *difficulty == rules.difficulty).unwrap_or(0);
+                rules.difficulty = Difficulty::ALL[Self::cycle(index, Difficulty::ALL.len(), step)];
+            }
+            START => {
+                let index = starts.iter().position(|start| *start == (practice.wave, practice.boss)).unwrap_or(0);
+                (practice.wave, practice.boss) = starts[Self::cycle(index, starts.len(), step)];
+            }
+            LIVES => practice.lives = Self::cycle(practice.lives as usize - 1, MAX_PRACTICE_LIVES as usize, step) as u32 + 1,
+            INFINITE_LIVES => practice.infinite_lives = !practice.infinite_lives,
+            SLOW_MOTION => practice.slow_motion = !practice.slow_motion,
+            _ => {}
+        }
+    }
+
+    fn play() -> SceneCommand {
+        SceneCommand::ReplaceAll(Box::new(Playing)).with_transition(TransitionKind::Iris)
+    }
+}
+
+impl Scene for PracticeSelect {
+    fn enter(&mut self, game: &mut Game) {
+        // Starts locked since the last practice fall back to the first one
+        let starts = Self::starts(game);
+        let practice = game.rules.practice.get_or_insert_default();
+        if !starts.contains(&(practice.wave, practice.boss)) {
+            (practice.wave, practice.boss) = starts[0];
+        }
+    }
+
+    fn update(&mut self, game: &mut Game) -> SceneCommand {
+        if MenuAction::Up.is_pressed(&game.gamepads) {
+            self.focus = Self::cycle(self.focus, ROWS, -1);
+        }
+        if MenuAction::Down.is_pressed(&game.gamepads) {
+            self.focus = Self::cycle(self.focus, ROWS, 1);
+        }
+        if MenuAction::Left.is_pressed(&game.gamepads) {
+            self.change(game, -1);
+        }
+        if MenuAction::Right.is_pressed(&game.gamepads) {
+            self.change(game, 1);
+        }
+
+        if MenuAction::Confirm.is_pressed(&game.gamepads) || is_key_pressed(KeyCode::Space) {
+            match self.focus {
+                MUTATORS => return SceneCommand::Push(Box::new(MutatorSelect::default())),
+                PLAY => return Self::play(),
+                _ => self.change(game, 1),
+            }
+        }
+        if is_key_pressed(KeyCode::S) {
+            return Self::play();
+        }
+
+        if MenuAction::Back.is_pressed(&game.gamepads) || is_key_pressed(KeyCode::B) {
+            game.rules.practice = None;
+            return SceneCommand::Pop;
+        }
+
+        SceneCommand::None
+    }
+
+    fn draw(&mut self, game: &mut Game) {
+        const FONT_SIZE: u16 = 24;
+        const ROW_HEIGHT: f32 = 34.0;
+
+        Self::draw_centered("Practice", 60.0, 48, GOLD);
+
+        let rules = game.rules;
+        let practice = rules.practice.unwrap_or_default();
+        let on_off = |value: bool| if value { "On" } else { "Off" };
+        let mutators = if rules.mutators.is_empty() {
+            "None".to_string()
+        } else {
+            format!("{} on", rules.mutators.iter().count())
+        };
+        let rows = [
+            format!("Mode: < {} >", rules.mode.name()),
+            format!("Difficulty: < {} >", rules.difficulty.name()),
+            format!("Start: < {} >", practice.start_name(rules.mode)),
+            format!("Lives: < {} >", practice.lives),
+            format!("Infinite lives: {}", on_off(practice.infinite_lives)),
+            format!("Slow motion: {}", on_off(practice.slow_motion)),
+            format!("Mutators: {}...", mutators),
+            "Start".to_string(),
+        ];
+
+        let left = screen_width() / 2.0 - 240.0;
+        let top = 130.0;
+        for (index, row) in rows.iter().enumerate() {
+            let color = if index == self.focus { GOLD } else { WHITE };
+            draw_text(row, left, top + ROW_HEIGHT * index as f32, FONT_SIZE.into(), color);
+        }
+
+        Self::draw_centered("Practice runs are not ranked", screen_height() - 60.0, FONT_SIZE, GRAY);
+        Self::draw_centered("(Left/Right) Change   (S)tart   (B)ack", screen_height() - 30.0, FONT_SIZE, GRAY);
+    }
+}
@@ -0,0 +1,38 @@
+use macroquad::ui::hash;
+
+use crate::{game::Game, input::MenuAction, menu::Menu, scene::{Scene, SceneCommand}, scenes::{AchievementList, Leaderboard, Statistics}, transition::TransitionKind};
+
+/// Menu of the leaderboards, achievements and stats.
+#[derive(Default)]
+pub struct Records {
+    focus: usize,
+}
+
+impl Records {
+    fn menu(&mut self, game: &Game, interactive: bool) -> Option<usize> {
+        let items = ["(L)eaders", "(A)wards", "S(t)ats", "(B)ack"];
+        Menu::focus_list_window(hash!(), "Records", &items, 420.0, &mut self.focus, &game.gamepads, interactive)
+    }
+}
+
+impl Scene for Records {
+    fn update(&mut self, game: &mut Game) -> SceneCommand {
+        match self.menu(game, true) {
+            Some(0) => return SceneCommand::Push(Box::new(Leaderboard::new())).with_transition(TransitionKind::Fade),
+            Some(1) => return SceneCommand::Push(Box::new(AchievementList::default())).with_transition(TransitionKind::Fade),
+            Some(2) => return SceneCommand::Push(Box::new(Statistics::default())).with_transition(TransitionKind::Fade),
+            Some(_) => return SceneCommand::Pop,
+            None => {}
+        }
+
+        if MenuAction::Back.is_pressed(&game.gamepads) {
+            return SceneCommand::Pop;
+        }
+
+        SceneCommand::None
+    }
+
+    fn frozen_menu(&mut self, game: &mut Game) {
+        self.menu(game, false);
+    }
+}
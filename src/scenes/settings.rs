@@ -0,0 +1,51 @@
+use macroquad::{input::{is_key_pressed, KeyCode}, ui::hash};
+
+use crate::{game::Game, menu::Menu, scene::{Scene, SceneCommand}};
+
+pub struct Settings;
+
+fn on_off(enabled: bool) -> &'static str {
+    if enabled { "On" } else { "Off" }
+}
+
+impl Settings {
+    fn menu(game: &Game, interactive: bool) -> Option<usize> {
+        let music = format!("(M)usic: {}", on_off(game.sound_config.music_enabled));
+        let effects = format!("(E)ffects: {}", on_off(game.sound_config.effects_enabled));
+        let pixel_perfect = format!("(P)recise hits: {}", on_off(game.pixel_perfect_collisions));
+        let debug = format!("(D)ebug: {}", on_off(game.debug_overlay.visible));
+
+        let items = [music.as_str(), effects.as_str(), pixel_perfect.as_str(), debug.as_str(), "(B)ack"];
+        Menu::list_window(hash!(), "Settings", &items, 720.0, interactive)
+    }
+}
+
+impl Scene for Settings {
+    fn exit(&mut self, game: &mut Game) {
+        game.save();
+    }
+
+    fn update(&mut self, game: &mut Game) -> SceneCommand {
+        match Self::menu(game, true) {
+            Some(0) => {
+                let enabled = !game.sound_config.music_enabled;
+                game.sound_config.set_music_enabled(enabled);
+            }
+            Some(1) => game.sound_config.effects_enabled = !game.sound_config.effects_enabled,
+            Some(2) => game.pixel_perfect_collisions = !game.pixel_perfect_collisions,
+            Some(3) => game.debug_overlay.toggle(),
+            Some(_) => return SceneCommand::Pop,
+            None => {}
+        }
+
+        if is_key_pressed(KeyCode::Escape) {
+            return SceneCommand::Pop;
+        }
+
+        SceneCommand::None
+    }
+
+    fn frozen_menu(&mut self, game: &mut Game) {
+        Self::menu(game, false);
+    }
+}
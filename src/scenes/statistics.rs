@@ -0,0 +1,62 @@
+use macroquad::{color::{Color, GOLD, GRAY, WHITE}, input::{is_key_pressed, KeyCode}, text::{draw_text, measure_text}, window::{screen_height, screen_width}};
+
+use crate::{game::Game, input::MenuAction, lifetime_stats, scene::{Scene, SceneCommand}};
+
+/// Stats of the last run next to the totals of every run.
+#[derive(Default)]
+pub struct Statistics {
+    export_message: Option<String>,
+}
+
+impl Statistics {
+    fn draw_centered(text: &str, y: f32, font_size: u16, color: Color) {
+        let text_dimensions = measure_text(text, None, font_size, 1.0);
+        draw_text(text, screen_width() / 2.0 - text_dimensions.width / 2.0, y, font_size.into(), color);
+    }
+}
+
+impl Scene for Statistics {
+    fn update(&mut self, game: &mut Game) -> SceneCommand {
+        if is_key_pressed(KeyCode::E) {
+            self.export_message = Some(match game.export_stats() {
+                Ok(location) => format!("Stats exported to {}", location),
+                Err(error) => format!("Could not export the stats: {}", error),
+            });
+        }
+
+        if MenuAction::Back.is_pressed(&game.gamepads) || is_key_pressed(KeyCode::B) {
+            return SceneCommand::Pop;
+        }
+
+        SceneCommand::None
+    }
+
+    fn draw(&mut self, game: &mut Game) {
+        const FONT_SIZE: u16 = 24;
+        const ROW_HEIGHT: f32 = 30.0;
+        const COLUMNS: [f32; 3] = [0.0, 260.0, 420.0];
+        const HEADERS: [&str; 3] = ["STAT", "LAST RUN", "LIFETIME"];
+
+        Self::draw_centered("Statistics", 60.0, 48, GOLD);
+
+        let left = screen_width() / 2.0 - 280.0;
+        let top = 120.0;
+        for (header, column) in HEADERS.iter().zip(COLUMNS) {
+            draw_text(header, left + column, top, FONT_SIZE.into(), GRAY);
+        }
+
+        let rows = lifetime_stats::stats_table(game.last_run.as_ref(), &game.lifetime_stats);
+        for (index, row) in rows.iter().enumerate() {
+            let y = top + ROW_HEIGHT * (index + 1) as f32;
+            let cells = [row.name.to_string(), row.format(row.last_run), row.format(Some(row.lifetime))];
+            for (cell, column) in cells.iter().zip(COLUMNS) {
+                draw_text(cell, left + column, y, FONT_SIZE.into(), WHITE);
+            }
+        }
+
+        if let Some(message) = &self.export_message {
+            Self::draw_centered(message, screen_height() - 60.0, FONT_SIZE, GRAY);
+        }
+        Self::draw_centered("(E)xport CSV   (B)ack", screen_height() - 30.0, FONT_SIZE, GRAY);
+    }
+}
@@ -1,6 +1,7 @@
-use macroquad::{color::WHITE, math::vec2, prelude::{gl_use_default_material, gl_use_material, load_material, Material, MaterialParams, ShaderSource, UniformDesc, UniformType}, texture::{draw_texture_ex, render_target, DrawTextureParams, FilterMode, RenderTarget}, window::{screen_height, screen_width}};
+use macroquad::{color::{BLACK, WHITE}, math::vec2, prelude::{gl_use_default_material, gl_use_material, load_material, Material, MaterialParams, ShaderSource, UniformDesc, UniformType}, shapes::draw_rectangle, texture::{draw_texture_ex, render_target, DrawTextureParams, FilterMode, RenderTarget}, window::{screen_height, screen_width}};
 
 const FRAGMENT_SHADER: &str = include_str!("starfield-shader.glsl");
+const DISSOLVE_FRAGMENT_SHADER: &str = include_str!("dissolve-shader.glsl");
 
 const VERTEX_SHADER: &str = "#version 100
 attribute vec3 position;
@@ -18,6 +19,21 @@ void main() {
 }
 ";
 
+const DISSOLVE_VERTEX_SHADER: &str = "#version 100
+attribute vec3 position;
+attribute vec2 texcoord;
+attribute vec4 color0;
+varying vec2 uv;
+
+uniform mat4 Model;
+uniform mat4 Projection;
+
+void main() {
+    gl_Position = Projection * Model * vec4(position, 1);
+    uv = texcoord;
+}
+";
+
 pub struct StarfieldShader {
     pub material: Material,
     pub direction_modifier: f32,
@@ -92,4 +108,52 @@ impl StarfieldShader {
         self.direction_modifier -= value;
     }
 
-}
\ No newline at end of file
+}
+
+/// Covers the screen with randomly placed black cells, used by scene
+/// transitions.
+pub struct DissolveShader {
+    pub material: Material,
+}
+
+impl Default for DissolveShader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DissolveShader {
+    pub fn new() -> Self {
+        DissolveShader {
+            material: Self::load_shader_material(),
+        }
+    }
+
+    /// Draws the dissolve, `progress` goes from 0 (nothing covered) to 1.
+    pub fn draw(&mut self, progress: f32) {
+        let material = &mut self.material;
+
+        material.set_uniform("iResolution", (screen_width(), screen_height()));
+        material.set_uniform("progress", progress);
+
+        gl_use_material(material);
+        draw_rectangle(0., 0., screen_width(), screen_height(), BLACK);
+        gl_use_default_material();
+    }
+
+    fn load_shader_material() -> Material {
+        load_material(
+            ShaderSource::Glsl {
+                vertex: DISSOLVE_VERTEX_SHADER,
+                fragment: DISSOLVE_FRAGMENT_SHADER
+            },
+            MaterialParams {
+                uniforms: vec![
+                    UniformDesc::new("iResolution", UniformType::Float2),
+                    UniformDesc::new("progress", UniformType::Float1),
+                ],
+                ..Default::default()
+            })
+            .unwrap()
+    }
+}
@@ -0,0 +1,297 @@
+use macroquad::{math::Vec2, rand::RandGenerator};
+
+use crate::{bullet::Bullet, combo::Combo, components::Team, director::Director, enemies::Enemies, enemy::EnemySize, game_mode::LEVEL_BONUS, hero::Hero, input::PlayerInput, mutators::Mutator, replay::Checkpoint, run_rules::RunRules, run_stats::RunStats, sprites_config::SpriteMasks, systems::{self, CollisionSystem}, world::{Entity, EntityPool, World}};
+
+/// Version of the rules of the simulation, kept in replays as they only
+/// play back the same with the rules that recorded them. Bump it with every
+/// change that makes a recorded run play differently.
+pub const SIMULATION_VERSION: u32 = 4;
+
+/// Something that happened during a step, for the game to play sounds and
+/// effects.
+#[derive(Clone, Copy, Debug)]
+pub enum SimulationEvent {
+    ShotFired,
+    /// `points` already include the multiplier.
+    EnemyDestroyed { position: Vec2, size: f32, points: u32, multiplier: u32 },
+    /// An enemy was hit but still has hit points.
+    EnemyDamaged { position: Vec2, damage: u32 },
+    HeroHit { position: Vec2 },
+    /// The combo reached a new multiplier.
+    MultiplierUp { multiplier: u32 },
+    /// The combo ended, by timing out or by a hit.
+    ComboBroken { chain: u32 },
+    /// A campaign level was cleared, earning `bonus` points.
+    LevelCleared { level: u32, bonus: u32 },
+}
+
+/// How a run ended.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum RunOutcome {
+    /// The ship lost its last life.
+    Destroyed,
+    /// The time limit of the mode was reached.
+    TimeUp,
+    /// The last campaign level was cleared.
+    Completed,
+}
+
+/// The rules of a run, without drawing, sounds or input devices.
+///
+/// A step only depends on its arguments and on the run's own random numbers,
+/// so stepping a new run with the frames of a replay plays it again exactly.
+pub struct Simulation {
+    pub rules: RunRules,
+    pub lives: u32,
+    pub score: u32,
+    pub world: World,
+    pub hero: Hero,
+    pub enemies: Enemies,
+    pub collisions: CollisionSystem,
+    pub stats: RunStats,
+    pub combo: Combo,
+    pub director: Director,
+    rng: RandGenerator,
+    despawned: Vec<Entity>,
+    events: Vec<SimulationEvent>,
+}
+
+impl Simulation {
+    /// Starts a run in a play area of `area` pixels.
+    pub fn new(seed: u64, area: Vec2, rules: RunRules) -> Self {
+        let mut world = World::default();
+        let hero = Hero::new(&mut world, area, rules.mutators.hero_scale());
+
+        let mut simulation = Simulation {
+            rules,
+            lives: rules.starting_lives(),
+            score: 0,
+            world,
+            hero,
+            enemies: Default::default(),
+            collisions: Default::default(),
+            stats: Default::default(),
+            combo: Default::default(),
+            director: Default::default(),
+            rng: RandGenerator::new(),
+            despawned: Vec::new(),
+            events: Vec::new(),
+        };
+        simulation.rng.srand(seed);
+        simulation.start_practice();
+
+        simulation
+    }
+
+    pub fn restart(&mut self, seed: u64, area: Vec2, rules: RunRules) {
+        self.rules = rules;
+        self.lives = rules.starting_lives();
+        self.score = 0;
+        self.stats = Default::default();
+        self.combo = Default::default();
+        self.director = Default::default();
+        self.rng.srand(seed);
+
+        self.world.clear();
+        self.enemies.restart();
+        self.hero.restart(&mut self.world, area, rules.mutators.hero_scale());
+        self.start_practice();
+    }
+
+    fn start_practice(&mut self) {
+        if let Some(practice) = self.rules.practice {
+            self.enemies.start_at(practice.wave, practice.boss);
+        }
+    }
+
+    /// How the run ended, `None` while it goes on.
+    pub fn outcome(&self) -> Option<RunOutcome> {
+        if self.lives < 1 {
+            Some(RunOutcome::Destroyed)
+        } else if self.enemies.completed {
+            Some(RunOutcome::Completed)
+        } else if self.rules.mode.time_limit().is_some_and(|limit| self.stats.time_survived >= limit) {
+            Some(RunOutcome::TimeUp)
+        } else {
+            None
+        }
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.outcome().is_some()
+    }
+
+    /// Seconds left before the time limit of the mode, if it has one.
+    pub fn time_left(&self) -> Option<f32> {
+        self.rules.mode.time_limit().map(|limit| (limit - self.stats.time_survived).max(0.0))
+    }
+
+    /// State compared along the run when verifying a replay.
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            score: self.score,
+            lives: self.lives,
+            wave: self.enemies.wave,
+        }
+    }
+
+    fn fire(&mut self) {
+        let position = self.hero.position(&self.world);
+        let hit_points = if self.rules.has(Mutator::RicochetBullets) { 2 } else { 1 };
+        if Bullet::spawn(&mut self.world, position, self.hero.get_speed() * 2., hit_points).is_some() {
+            self.stats.shots_fired += 1;
+            self.events.push(SimulationEvent::ShotFired);
+        }
+    }
+
+    /// Returns whether the ship was hit.
+    fn check_collisions(&mut self, area: Vec2, masks: Option<&SpriteMasks>) -> bool {
+        let hits = self.collisions.run(&self.world, area.x, area.y, masks);
+        let hero_hit = hits.iter().any(|&(a, b)| a == self.hero.entity || b == self.hero.entity);
+        self.stats.shots_hit += hits
+            .iter()
+            .filter(|(a, b)| matches!((a.pool(), b.pool()), (EntityPool::Bullets, EntityPool::Enemies) | (EntityPool::Enemies, EntityPool::Bullets)))
+            .count() as u32;
+
+        if self.rules.has(Mutator::RicochetBullets) {
+            self.ricochet();
+        }
+
+        for destroyed in self.collisions.apply_damage(&mut self.world) {
+            if destroyed.pool == EntityPool::Enemies {
+                let multiplier = self.combo.multiplier();
+                self.combo.kill();
+                self.stats.max_combo = self.stats.max_combo.max(self.combo.chain);
+                if self.combo.multiplier() > multiplier {
+                    self.events.push(SimulationEvent::MultiplierUp {
+                        multiplier: self.combo.multiplier(),
+                    });
+                }
+
+                // Giant enemies are worth the points of their usual size
+                let size = destroyed.size / self.rules.mutators.enemy_scale();
+                let points = size.round() as u32 * self.combo.multiplier();
+                let points = self.rules.mode.kill_points(points, self.enemies.wave);
+                let points = (points as f32 * self.rules.score_multiplier()).round() as u32;
+                self.score += points;
+                self.stats.enemy_destroyed(EnemySize::from_size(size));
+                self.events.push(SimulationEvent::EnemyDestroyed {
+                    position: destroyed.position,
+                    size: destroyed.size,
+                    points,
+                    multiplier: self.combo.multiplier(),
+                });
+            }
+        }
+
+        for damaged in self.collisions.damaged() {
+            if damaged.team == Some(Team::Enemy) {
+                self.events.push(SimulationEvent::EnemyDamaged {
+                    position: damaged.position,
+                    damage: damaged.damage,
+                });
+            }
+        }
+
+        if hero_hit {
+            if self.rules.loses_lives() {
+                self.lives = if self.rules.has(Mutator::OneHitKills) { 0 } else { self.lives.saturating_sub(1) };
+            }
+            self.stats.hero_hit();
+            self.events.push(SimulationEvent::HeroHit {
+                position: self.hero.position(&self.world),
+            });
+            self.break_combo();
+        }
+
+        hero_hit
+    }
+
+    /// Sends the bolts of the hero that hit an enemy and will survive the
+    /// hit away from it.
+    fn ricochet(&mut self) {
+        for index in 0..self.collisions.hits().len() {
+            let Some(&(a, b)) = self.collisions.hits().get(index) else {
+                break;
+            };
+            let (bullet, enemy) = match (a.pool(), b.pool()) {
+                (EntityPool::Bullets, EntityPool::Enemies) => (a, b),
+                (EntityPool::Enemies, EntityPool::Bullets) => (b, a),
+                _ => continue,
+            };
+            if self.world.healths.get(bullet).is_none_or(|health| health.hit_points < 2) {
+                continue;
+            }
+
+            let (Some(bullet_transform), Some(enemy_transform)) = (self.world.transforms.get(bullet), self.world.transforms.get(enemy)) else {
+                continue;
+            };
+            let left = bullet_transform.position.x < enemy_transform.position.x;
+            Bullet::ricochet(&mut self.world, bullet, left);
+        }
+    }
+
+    fn break_combo(&mut self) {
+        let chain = self.combo.break_chain();
+        if chain > 0 {
+            self.events.push(SimulationEvent::ComboBroken { chain });
+        }
+    }
+
+    /// Advances the run by `delta_time` seconds scaled by
+    /// `rules.time_scale()`, so practice runs in slow motion advance less, in
+    /// a play area of `area` pixels. Hits are checked against the pixel masks
+    /// when they are given.
+    pub fn step(&mut self, delta_time: f32, input: PlayerInput, area: Vec2, masks: Option<&SpriteMasks>) -> &[SimulationEvent] {
+        let delta_time = delta_time * self.rules.time_scale();
+        self.events.clear();
+        self.stats.time_survived += delta_time;
+        if let Some(chain) = self.combo.update(delta_time) {
+            self.events.push(SimulationEvent::ComboBroken { chain });
+        }
+
+        let steering = if self.rules.has(Mutator::MirroredControls) { input.mirrored() } else { input };
+        self.hero.check_inputs(&mut self.world, steering);
+        if input.contains(PlayerInput::FIRE) {
+            self.fire();
+        }
+
+        let (wave, completed) = (self.enemies.wave, self.enemies.completed);
+        self.enemies.update(&mut self.world, &self.rng, delta_time, area.x, self.rules, &self.director);
+        if self.enemies.wave != wave || self.enemies.completed != completed {
+            self.stats.wave_ended();
+
+            if self.rules.mode.has_levels() {
+                let bonus = LEVEL_BONUS * wave;
+                self.score += bonus;
+                self.events.push(SimulationEvent::LevelCleared { level: wave, bonus });
+            }
+        }
+
+        let hero_position = self.hero.position(&self.world);
+        systems::movement(&mut self.world, delta_time);
+        if self.rules.has(Mutator::ScreenWrap) {
+            // Measured before wrapping, the jump to the other side isn't flown
+            self.stats.distance_flown += self.hero.position(&self.world).distance(hero_position);
+            self.hero.wrap_around(&mut self.world, area);
+        } else {
+            self.hero.keep_on_screen(&mut self.world, area);
+            self.stats.distance_flown += self.hero.position(&self.world).distance(hero_position);
+        }
+        if self.rules.has(Mutator::RicochetBullets) {
+            Bullet::bounce_off_walls(&mut self.world, area.x);
+        }
+
+        systems::animation(&mut self.world, delta_time);
+        systems::lifetimes(&mut self.world, delta_time, &mut self.despawned);
+        systems::cull_offscreen(&mut self.world, area.x, area.y, &mut self.despawned);
+
+        let kills = self.stats.enemies_destroyed();
+        let hero_hit = self.check_collisions(area, masks);
+
+        let kills = self.stats.enemies_destroyed() - kills;
+        self.director.update(delta_time, kills, hero_hit, self.lives, self.rules.starting_lives());
+
+        &self.events
+    }
+}
@@ -1,9 +1,13 @@
-use macroquad::audio::{load_sound, play_sound, play_sound_once, PlaySoundParams, Sound};
+use macroquad::audio::{load_sound, play_sound, play_sound_once, set_sound_volume, PlaySoundParams, Sound};
 
 pub struct SoundConfig {
     pub theme_music: Sound,
     pub sound_explosion: Sound,
     pub sound_laser: Sound,
+    pub sound_combo: Sound,
+
+    pub music_enabled: bool,
+    pub effects_enabled: bool,
 }
 
 impl SoundConfig {
@@ -11,11 +15,15 @@ impl SoundConfig {
         let theme_music = load_sound("assets/8bit-spaceshooter.ogg").await.unwrap();
         let sound_explosion = load_sound("assets/explosion.wav").await.unwrap();
         let sound_laser = load_sound("assets/laser.wav").await.unwrap();
+        let sound_combo = load_sound("assets/combo.wav").await.unwrap();
 
         SoundConfig {
             theme_music,
             sound_explosion,
             sound_laser,
+            sound_combo,
+            music_enabled: true,
+            effects_enabled: true,
         }
     }
 
@@ -24,16 +32,39 @@ impl SoundConfig {
             &self.theme_music,
             PlaySoundParams {
                 looped: true,
-                volume: 1.,
+                volume: if self.music_enabled { 1. } else { 0. },
             },
         );
     }
 
+    /// The theme keeps looping while muted, so it resumes in place.
+    pub fn set_music_enabled(&mut self, enabled: bool) {
+        self.music_enabled = enabled;
+        set_sound_volume(&self.theme_music, if enabled { 1. } else { 0. });
+    }
+
     pub fn play_sound_explosion(&self) {
-        play_sound_once(&self.sound_explosion);
+        if self.effects_enabled {
+            play_sound_once(&self.sound_explosion);
+        }
+    }
+
+    /// Louder for each step of the multiplier.
+    pub fn play_sound_combo(&self, multiplier: u32) {
+        if self.effects_enabled {
+            play_sound(
+                &self.sound_combo,
+                PlaySoundParams {
+                    looped: false,
+                    volume: (0.3 + 0.1 * multiplier as f32).min(1.0),
+                },
+            );
+        }
     }
 
     pub fn play_sound_laser(&self) {
-        play_sound_once(&self.sound_laser);
+        if self.effects_enabled {
+            play_sound_once(&self.sound_laser);
+        }
     }
-}
\ No newline at end of file
+}
@@ -1,6 +1,92 @@
-use macroquad::{file::set_pc_assets_folder, prelude::animation::{AnimatedSprite, Animation}, texture::{build_textures_atlas, load_texture, FilterMode, Texture2D}};
+use std::{fs, io, path::Path};
 
-use crate::constants::ASSETS_PATH;
+use macroquad::{file::set_pc_assets_folder, math::Rect, prelude::ImageFormat, texture::{build_textures_atlas, load_image, FilterMode, Image, Texture2D}};
+
+use crate::{collision_mask::SpriteMask, components::SpriteTexture, constants::ASSETS_PATH};
+
+/// Frames per second of every sprite animation.
+pub const ANIMATION_FPS: u32 = 12;
+
+const SHIP_FRAME_SIZE: (u32, u32) = (16, 24);
+const BULLET_FRAME_SIZE: (u32, u32) = (16, 16);
+const ENEMY_SMALL_FRAME_SIZE: (u32, u32) = (17, 16);
+const ENEMY_MEDIUM_FRAME_SIZE: (u32, u32) = (32, 16);
+const ENEMY_BIG_FRAME_SIZE: (u32, u32) = (32, 32);
+
+/// Row of a sprite sheet played as an animation.
+#[derive(Clone, Copy, Debug)]
+pub struct Animation {
+    pub row: u32,
+    pub frames: u32,
+}
+
+/// Frame size and animations of a sprite sheet, shared by every sprite
+/// drawn from it so spawning one allocates nothing.
+#[derive(Debug)]
+pub struct AnimationTable {
+    pub frame_size: (u32, u32),
+    pub animations: &'static [Animation],
+}
+
+/// Round bullet, then bolt.
+static BULLET_ANIMATIONS: AnimationTable = AnimationTable {
+    frame_size: BULLET_FRAME_SIZE,
+    animations: &[Animation { row: 0, frames: 2 }, Animation { row: 1, frames: 2 }],
+};
+/// Idle, left and right.
+static SHIP_ANIMATIONS: AnimationTable = AnimationTable {
+    frame_size: SHIP_FRAME_SIZE,
+    animations: &[Animation { row: 0, frames: 2 }, Animation { row: 2, frames: 2 }, Animation { row: 4, frames: 2 }],
+};
+static ENEMY_SMALL_ANIMATIONS: AnimationTable = AnimationTable {
+    frame_size: ENEMY_SMALL_FRAME_SIZE,
+    animations: &[Animation { row: 0, frames: 2 }],
+};
+static ENEMY_MEDIUM_ANIMATIONS: AnimationTable = AnimationTable {
+    frame_size: ENEMY_MEDIUM_FRAME_SIZE,
+    animations: &[Animation { row: 0, frames: 2 }],
+};
+static ENEMY_BIG_ANIMATIONS: AnimationTable = AnimationTable {
+    frame_size: ENEMY_BIG_FRAME_SIZE,
+    animations: &[Animation { row: 0, frames: 2 }],
+};
+
+/// Animation and frame a sprite is at in its `AnimationTable`.
+#[derive(Clone, Copy, Debug)]
+pub struct SpriteAnimation {
+    table: &'static AnimationTable,
+    animation: usize,
+    frame: u32,
+}
+
+impl SpriteAnimation {
+    pub fn new(table: &'static AnimationTable, animation: usize) -> Self {
+        SpriteAnimation { table, animation, frame: 0 }
+    }
+
+    pub fn current_animation(&self) -> usize {
+        self.animation
+    }
+
+    /// Plays another animation of the table, keeping the frame.
+    pub fn set_animation(&mut self, animation: usize) {
+        self.animation = animation.min(self.table.animations.len() - 1);
+        self.set_frame(self.frame);
+    }
+
+    /// Wraps `frame` around the length of the animation.
+    pub fn set_frame(&mut self, frame: u32) {
+        self.frame = frame % self.table.animations[self.animation].frames;
+    }
+
+    /// Part of the sprite sheet to draw.
+    pub fn source_rect(&self) -> Rect {
+        let (width, height) = self.table.frame_size;
+        let row = self.table.animations[self.animation].row;
+
+        Rect::new((width * self.frame) as f32, (height * row) as f32, width as f32, height as f32)
+    }
+}
 
 pub struct SpritesConfig {
     pub ship_texture: Texture2D,
@@ -9,6 +95,59 @@ pub struct SpritesConfig {
     pub enemy_small_texture: Texture2D,
     pub enemy_medium_texture: Texture2D,
     pub enemy_big_texture: Texture2D,
+
+    pub masks: SpriteMasks,
+}
+
+/// Collision masks of the sprite sheets, which only need the images so they
+/// can be built without a window.
+pub struct SpriteMasks {
+    pub ship: SpriteMask,
+    pub bullet: SpriteMask,
+    pub enemy_small: SpriteMask,
+    pub enemy_medium: SpriteMask,
+    pub enemy_big: SpriteMask,
+}
+
+impl SpriteMasks {
+    pub fn new(ship: &Image, bullet: &Image, enemy_small: &Image, enemy_medium: &Image, enemy_big: &Image) -> Self {
+        SpriteMasks {
+            ship: SpriteMask::new(ship, SHIP_FRAME_SIZE.0, SHIP_FRAME_SIZE.1),
+            bullet: SpriteMask::new(bullet, BULLET_FRAME_SIZE.0, BULLET_FRAME_SIZE.1),
+            enemy_small: SpriteMask::new(enemy_small, ENEMY_SMALL_FRAME_SIZE.0, ENEMY_SMALL_FRAME_SIZE.1),
+            enemy_medium: SpriteMask::new(enemy_medium, ENEMY_MEDIUM_FRAME_SIZE.0, ENEMY_MEDIUM_FRAME_SIZE.1),
+            enemy_big: SpriteMask::new(enemy_big, ENEMY_BIG_FRAME_SIZE.0, ENEMY_BIG_FRAME_SIZE.1),
+        }
+    }
+
+    /// Reads the images straight from the assets folder, for the tools that
+    /// run without a window.
+    pub fn load(assets_path: &Path) -> io::Result<Self> {
+        let load = |filename: &str| {
+            let path = assets_path.join(filename);
+            let bytes = fs::read(&path).map_err(|error| io::Error::new(error.kind(), format!("{}: {}", path.display(), error)))?;
+            Image::from_file_with_format(&bytes, Some(ImageFormat::Png))
+                .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", path.display(), error)))
+        };
+
+        Ok(SpriteMasks::new(
+            &load("ship.png")?,
+            &load("laser-bolts.png")?,
+            &load("enemy-small.png")?,
+            &load("enemy-medium.png")?,
+            &load("enemy-big.png")?,
+        ))
+    }
+
+    pub fn mask(&self, texture: SpriteTexture) -> &SpriteMask {
+        match texture {
+            SpriteTexture::Ship => &self.ship,
+            SpriteTexture::Bullet => &self.bullet,
+            SpriteTexture::EnemySmall => &self.enemy_small,
+            SpriteTexture::EnemyMedium => &self.enemy_medium,
+            SpriteTexture::EnemyBig => &self.enemy_big,
+        }
+    }
 }
 
 
@@ -16,20 +155,22 @@ impl SpritesConfig {
     pub async fn new() -> SpritesConfig {
         set_pc_assets_folder(ASSETS_PATH);
 
-        let ship_texture = SpritesConfig::load_asset_texture("ship.png").await;
-        let bullet_texture = SpritesConfig::load_asset_texture("laser-bolts.png").await;
-        let explosion_texture = SpritesConfig::load_asset_texture("explosion.png").await;
-        let enemy_small_texture = SpritesConfig::load_asset_texture("enemy-small.png").await;
-        let enemy_medium_texture = SpritesConfig::load_asset_texture("enemy-medium.png").await;
-        let enemy_big_texture = SpritesConfig::load_asset_texture("enemy-big.png").await;
+        let ship_image = SpritesConfig::load_asset_image("ship.png").await;
+        let bullet_image = SpritesConfig::load_asset_image("laser-bolts.png").await;
+        let explosion_image = SpritesConfig::load_asset_image("explosion.png").await;
+        let enemy_small_image = SpritesConfig::load_asset_image("enemy-small.png").await;
+        let enemy_medium_image = SpritesConfig::load_asset_image("enemy-medium.png").await;
+        let enemy_big_image = SpritesConfig::load_asset_image("enemy-big.png").await;
 
         let config = SpritesConfig {
-            ship_texture,
-            bullet_texture,
-            explosion_texture,
-            enemy_small_texture,
-            enemy_medium_texture,
-            enemy_big_texture,
+            ship_texture: SpritesConfig::create_texture(&ship_image),
+            bullet_texture: SpritesConfig::create_texture(&bullet_image),
+            explosion_texture: SpritesConfig::create_texture(&explosion_image),
+            enemy_small_texture: SpritesConfig::create_texture(&enemy_small_image),
+            enemy_medium_texture: SpritesConfig::create_texture(&enemy_medium_image),
+            enemy_big_texture: SpritesConfig::create_texture(&enemy_big_image),
+
+            masks: SpriteMasks::new(&ship_image, &bullet_image, &enemy_small_image, &enemy_medium_image, &enemy_big_image),
         };
 
         build_textures_atlas();
@@ -37,116 +178,48 @@ impl SpritesConfig {
         config
     }
 
-    async fn load_asset_texture(filename: &str) -> Texture2D {
-        let texture = load_texture(filename)
+    pub fn texture(&self, texture: SpriteTexture) -> &Texture2D {
+        match texture {
+            SpriteTexture::Ship => &self.ship_texture,
+            SpriteTexture::Bullet => &self.bullet_texture,
+            SpriteTexture::EnemySmall => &self.enemy_small_texture,
+            SpriteTexture::EnemyMedium => &self.enemy_medium_texture,
+            SpriteTexture::EnemyBig => &self.enemy_big_texture,
+        }
+    }
+
+    async fn load_asset_image(filename: &str) -> Image {
+        load_image(filename)
             .await
-            .expect(&format!("Couldn't load file {}", filename));
+            .unwrap_or_else(|_| panic!("Couldn't load file {}", filename))
+    }
+
+    fn create_texture(image: &Image) -> Texture2D {
+        let texture = Texture2D::from_image(image);
 
         texture.set_filter(FilterMode::Nearest);
 
         texture
     }
 
-    pub fn get_bullet_sprite() -> AnimatedSprite {
-        let mut bullet_sprite = AnimatedSprite::new(
-            16,
-            16,
-            &[
-                Animation {
-                    name: "bullet".into(),
-                    row: 0,
-                    frames: 2,
-                    fps: 12,
-                },
-                Animation {
-                    name: "bolt".into(),
-                    row: 1,
-                    frames: 2,
-                    fps: 12,
-                },
-            ],
-            true
-        );
-
-        bullet_sprite.set_animation(1);
-
-        bullet_sprite
-    }
-
-    pub fn get_ship_sprite() -> AnimatedSprite {
-        AnimatedSprite::new(
-            16,
-            24,
-            &[
-                Animation {
-                    name: "idle".to_string(),
-                    row: 0,
-                    frames: 2,
-                    fps: 12,
-                },
-                Animation {
-                    name: "left".to_string(),
-                    row: 2,
-                    frames: 2,
-                    fps: 12,
-                },
-                Animation {
-                    name: "right".to_string(),
-                    row: 4,
-                    frames: 2,
-                    fps: 12,
-                },
-            ],
-            true,
-        )
-    }
-
-    pub fn get_enemy_small_sprite() -> AnimatedSprite {
-        AnimatedSprite::new(
-            17,
-            16,
-            &[
-                Animation {
-                    name: "enemy_small".to_string(),
-                    row: 0,
-                    frames: 2,
-                    fps: 12,
-                },
-            ],
-            true,
-        )
-    }
-
-    pub fn get_enemy_medium_sprite() -> AnimatedSprite {
-        AnimatedSprite::new(
-            32,
-            16,
-            &[
-                Animation {
-                    name: "enemy_medium".to_string(),
-                    row: 0,
-                    frames: 2,
-                    fps: 12,
-                },
-            ],
-            true,
-        )
-    }
-
-    pub fn get_enemy_big_sprite() -> AnimatedSprite {
-        AnimatedSprite::new(
-            32,
-            32,
-            &[
-                Animation {
-                    name: "enemy_big".to_string(),
-                    row: 0,
-                    frames: 2,
-                    fps: 12,
-                },
-            ],
-            true,
-        )
+    pub fn get_bullet_sprite() -> SpriteAnimation {
+        // The bolt rather than the round bullet
+        SpriteAnimation::new(&BULLET_ANIMATIONS, 1)
+    }
+
+    pub fn get_ship_sprite() -> SpriteAnimation {
+        SpriteAnimation::new(&SHIP_ANIMATIONS, 0)
     }
 
+    pub fn get_enemy_small_sprite() -> SpriteAnimation {
+        SpriteAnimation::new(&ENEMY_SMALL_ANIMATIONS, 0)
+    }
+
+    pub fn get_enemy_medium_sprite() -> SpriteAnimation {
+        SpriteAnimation::new(&ENEMY_MEDIUM_ANIMATIONS, 0)
+    }
+
+    pub fn get_enemy_big_sprite() -> SpriteAnimation {
+        SpriteAnimation::new(&ENEMY_BIG_ANIMATIONS, 0)
+    }
 }
\ No newline at end of file
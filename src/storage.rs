@@ -0,0 +1,173 @@
+use std::io;
+
+/// Where persisted data is kept: files on native builds, the browser's
+/// localStorage on the web.
+///
+/// Values are whole texts addressed by a key, written in one go.
+pub trait Storage {
+    /// Returns `None` when nothing was stored under `key`.
+    fn read(&self, key: &str) -> io::Result<Option<String>>;
+    /// Replaces the value of `key`, a failed write keeps the previous one.
+    fn write(&mut self, key: &str, value: &str) -> io::Result<()>;
+    fn remove(&mut self, key: &str) -> io::Result<()>;
+    /// Where `key` is stored, for messages to the player.
+    fn location(&self, key: &str) -> String;
+}
+
+/// Storage of the platform the game runs on.
+pub fn platform_storage() -> Box<dyn Storage> {
+    #[cfg(not(target_arch = "wasm32"))]
+    return Box::new(file::FileStorage::new(file::data_directory()));
+
+    #[cfg(target_arch = "wasm32")]
+    return Box::new(local_storage::LocalStorage);
+}
+
+/// Storage older versions wrote to, only read to migrate it.
+pub fn legacy_storage() -> Box<dyn Storage> {
+    // Legacy files were written in the working directory
+    #[cfg(not(target_arch = "wasm32"))]
+    return Box::new(file::FileStorage::new(std::path::PathBuf::from(".")));
+
+    #[cfg(target_arch = "wasm32")]
+    return Box::new(local_storage::LocalStorage);
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod file {
+    use std::{env, fs, io::{self, Write}, path::PathBuf};
+
+    use crate::constants::GAME_DIRECTORY_NAME;
+
+    use super::Storage;
+
+    /// The platform data directory when it can be found, the working
+    /// directory otherwise.
+    pub fn data_directory() -> PathBuf {
+        let base = if cfg!(target_os = "windows") {
+            env::var_os("APPDATA").map(PathBuf::from)
+        } else if cfg!(target_os = "macos") {
+            env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+        } else {
+            env::var_os("XDG_DATA_HOME")
+                .map(PathBuf::from)
+                .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+        };
+
+        base.map_or_else(|| PathBuf::from("."), |base| base.join(GAME_DIRECTORY_NAME))
+    }
+
+    /// Keys are paths relative to a root directory.
+    pub struct FileStorage {
+        root: PathBuf,
+    }
+
+    impl FileStorage {
+        pub fn new(root: PathBuf) -> Self {
+            FileStorage { root }
+        }
+    }
+
+    impl Storage for FileStorage {
+        fn read(&self, key: &str) -> io::Result<Option<String>> {
+            match fs::read_to_string(self.root.join(key)) {
+                Ok(text) => Ok(Some(text)),
+                Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+                Err(error) => Err(error),
+            }
+        }
+
+        /// Writes a temporary file renamed over the old one, so a failed
+        /// write never leaves a half written file.
+        fn write(&mut self, key: &str, value: &str) -> io::Result<()> {
+            let path = self.root.join(key);
+            if let Some(directory) = path.parent() {
+                fs::create_dir_all(directory)?;
+            }
+
+            let mut temporary_path = path.clone().into_os_string();
+            temporary_path.push(".tmp");
+
+            let mut file = fs::File::create(&temporary_path)?;
+            file.write_all(value.as_bytes())?;
+            file.sync_all()?;
+
+            fs::rename(&temporary_path, &path)
+        }
+
+        fn remove(&mut self, key: &str) -> io::Result<()> {
+            match fs::remove_file(self.root.join(key)) {
+                Err(error) if error.kind() != io::ErrorKind::NotFound => Err(error),
+                _ => Ok(()),
+            }
+        }
+
+        fn location(&self, key: &str) -> String {
+            self.root.join(key).display().to_string()
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod local_storage {
+    use std::io;
+
+    use crate::constants::GAME_DIRECTORY_NAME;
+
+    use super::Storage;
+
+    // Provided by the `storage.js` plugin loaded by `index.html`
+    unsafe extern "C" {
+        /// Length in bytes of the value, -1 when there is none.
+        fn storage_length(key: *const u8, key_length: u32) -> i32;
+        fn storage_read(key: *const u8, key_length: u32, buffer: *mut u8, buffer_length: u32);
+        /// Returns 0 when the browser refused the value, usually for quota.
+        fn storage_write(key: *const u8, key_length: u32, value: *const u8, value_length: u32) -> i32;
+        fn storage_remove(key: *const u8, key_length: u32);
+    }
+
+    /// The browser's localStorage. It is shared with the rest of the site, so
+    /// keys are prefixed with the name of the game.
+    pub struct LocalStorage;
+
+    fn prefixed(key: &str) -> String {
+        format!("{}/{}", GAME_DIRECTORY_NAME, key)
+    }
+
+    impl Storage for LocalStorage {
+        fn read(&self, key: &str) -> io::Result<Option<String>> {
+            let key = prefixed(key);
+            let length = unsafe { storage_length(key.as_ptr(), key.len() as u32) };
+            if length < 0 {
+                return Ok(None);
+            }
+
+            let mut buffer = vec![0; length as usize];
+            unsafe { storage_read(key.as_ptr(), key.len() as u32, buffer.as_mut_ptr(), buffer.len() as u32) };
+
+            String::from_utf8(buffer)
+                .map(Some)
+                .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+        }
+
+        fn write(&mut self, key: &str, value: &str) -> io::Result<()> {
+            let key = prefixed(key);
+            let written = unsafe { storage_write(key.as_ptr(), key.len() as u32, value.as_ptr(), value.len() as u32) };
+            if written == 0 {
+                return Err(io::Error::other("the browser refused to store the data"));
+            }
+
+            Ok(())
+        }
+
+        fn remove(&mut self, key: &str) -> io::Result<()> {
+            let key = prefixed(key);
+            unsafe { storage_remove(key.as_ptr(), key.len() as u32) };
+            Ok(())
+        }
+
+        fn location(&self, key: &str) -> String {
+            format!("browser storage ({})", prefixed(key))
+        }
+    }
+}
@@ -0,0 +1,284 @@
+use macroquad::{
+    color::WHITE,
+    math::{Rect, Vec2},
+    texture::{draw_texture_ex, DrawTextureParams},
+};
+
+use crate::{
+    collidable::Collidable,
+    collision_grid::CollisionGrid,
+    collision_layers::CollisionLayer,
+    collision_mask::MaskedSprite,
+    components::{SpriteTexture, Team},
+    shape::ShapeType,
+    sprites_config::{SpriteMasks, SpritesConfig, ANIMATION_FPS},
+    world::{Entity, EntityPool, World},
+};
+
+/// Moves every entity by its velocity.
+pub fn movement(world: &mut World, delta_time: f32) {
+    for (entity, velocity) in world.velocities.iter() {
+        if let Some(transform) = world.transforms.get_mut(entity) {
+            transform.previous_position = transform.position;
+            transform.position += velocity.linear * delta_time;
+            transform.rotation += velocity.angular * delta_time;
+        }
+    }
+}
+
+/// Advances the sprite animations by the step's time rather than the frame
+/// time, so replays play them back the same way.
+pub fn animation(world: &mut World, delta_time: f32) {
+    for (_, sprite) in world.sprites.iter_mut() {
+        sprite.elapsed += delta_time;
+        sprite.animation.set_frame((sprite.elapsed * ANIMATION_FPS as f32) as u32);
+    }
+}
+
+/// Counts lifetimes down and despawns the entities that ran out.
+pub fn lifetimes(world: &mut World, delta_time: f32, expired: &mut Vec<Entity>) {
+    expired.clear();
+    for (entity, lifetime) in world.lifetimes.iter_mut() {
+        lifetime.remaining -= delta_time;
+        if lifetime.remaining <= 0.0 {
+            expired.push(entity);
+        }
+    }
+
+    for entity in expired.iter() {
+        world.despawn(*entity);
+    }
+}
+
+/// Despawns moving entities that left the screen and are heading away from it.
+pub fn cull_offscreen(world: &mut World, width: f32, height: f32, culled: &mut Vec<Entity>) {
+    culled.clear();
+    for (entity, velocity) in world.velocities.iter() {
+        let Some(transform) = world.transforms.get(entity) else {
+            continue;
+        };
+
+        let half = transform.size / 2.0;
+        let position = transform.position;
+        let linear = velocity.linear;
+
+        if (position.x < -half && linear.x < 0.0)
+            || (position.x > width + half && linear.x > 0.0)
+            || (position.y < -half && linear.y < 0.0)
+            || (position.y > height + half && linear.y > 0.0)
+        {
+            culled.push(entity);
+        }
+    }
+
+    for entity in culled.iter() {
+        world.despawn(*entity);
+    }
+}
+
+pub fn render(world: &World, sprites_config: &SpritesConfig) {
+    for (entity, sprite) in world.sprites.iter() {
+        let Some(transform) = world.transforms.get(entity) else {
+            continue;
+        };
+
+        let dest = draw_rect(transform.position, sprite.draw_size);
+
+        draw_texture_ex(
+            sprites_config.texture(sprite.texture),
+            dest.x,
+            dest.y,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(sprite.draw_size),
+                source: Some(sprite.animation.source_rect()),
+                rotation: transform.rotation,
+                ..Default::default()
+            },
+        );
+    }
+}
+
+fn draw_rect(position: Vec2, draw_size: Vec2) -> Rect {
+    Rect::new(
+        position.x - draw_size.x / 2.0,
+        position.y - draw_size.y / 2.0,
+        draw_size.x,
+        draw_size.y,
+    )
+}
+
+/// Snapshot of a colliding entity for the collision grid.
+struct Body {
+    entity: Entity,
+    shape_type: ShapeType,
+    layer: CollisionLayer,
+    position: Vec2,
+    previous_position: Vec2,
+    rotation: f32,
+    size: f32,
+    /// Hit points left, `None` for entities that are never destroyed by hits.
+    hit_points: Option<u32>,
+    sprite: Option<(SpriteTexture, Rect, Vec2)>,
+}
+
+impl Collidable for Body {
+    fn shape_type(&self) -> ShapeType {
+        self.shape_type
+    }
+
+    fn position(&self) -> Vec2 {
+        self.position
+    }
+
+    fn size(&self) -> f32 {
+        self.size
+    }
+
+    fn layer(&self) -> CollisionLayer {
+        self.layer
+    }
+
+    fn previous_position(&self) -> Vec2 {
+        self.previous_position
+    }
+
+    fn rotation(&self) -> f32 {
+        self.rotation
+    }
+
+    fn is_active(&self) -> bool {
+        self.hit_points != Some(0)
+    }
+
+    fn on_hit(&mut self, _other: CollisionLayer) {
+        if let Some(hit_points) = self.hit_points.as_mut() {
+            *hit_points = hit_points.saturating_sub(1);
+        }
+    }
+
+    fn pixel_mask<'a>(&self, masks: &'a SpriteMasks) -> Option<MaskedSprite<'a>> {
+        let (texture, source_rect, draw_size) = self.sprite?;
+
+        Some(MaskedSprite {
+            mask: masks.mask(texture).frame(source_rect),
+            dest: draw_rect(self.position, draw_size),
+            bounds: self.local_rect(),
+            rotation: self.rotation,
+            motion: self.position - self.previous_position,
+        })
+    }
+}
+
+/// An entity destroyed by collisions this step.
+#[derive(Clone, Copy, Debug)]
+pub struct Destroyed {
+    pub pool: EntityPool,
+    pub team: Option<Team>,
+    pub position: Vec2,
+    pub size: f32,
+}
+
+/// An entity hit by collisions this step that still has hit points.
+#[derive(Clone, Copy, Debug)]
+pub struct Damaged {
+    pub team: Option<Team>,
+    pub position: Vec2,
+    pub damage: u32,
+}
+
+/// Collision and damage pass over every entity with a collider.
+///
+/// The buffers are kept between frames so steady-state play doesn't allocate.
+#[derive(Default)]
+pub struct CollisionSystem {
+    pub grid: CollisionGrid,
+    bodies: Vec<Body>,
+    hits: Vec<(Entity, Entity)>,
+    destroyed: Vec<Destroyed>,
+    damaged: Vec<Damaged>,
+}
+
+impl CollisionSystem {
+    /// Finds the collisions of the step and returns the pairs of entities
+    /// that hit each other.
+    pub fn run(&mut self, world: &World, width: f32, height: f32, masks: Option<&SpriteMasks>) -> &[(Entity, Entity)] {
+        self.bodies.clear();
+        for (entity, collider) in world.colliders.iter() {
+            let Some(transform) = world.transforms.get(entity) else {
+                continue;
+            };
+
+            let sprite = world.sprites.get(entity).map(|sprite| (sprite.texture, sprite.animation.source_rect(), sprite.draw_size));
+
+            self.bodies.push(Body {
+                entity,
+                shape_type: collider.shape_type,
+                layer: collider.layer,
+                position: transform.position,
+                previous_position: transform.previous_position,
+                rotation: transform.rotation,
+                size: transform.size,
+                hit_points: world.healths.get(entity).map(|health| health.hit_points),
+                sprite,
+            });
+        }
+
+        self.grid.rebuild(width, height, &self.bodies);
+        let contacts = self.grid.resolve(&mut self.bodies, masks);
+
+        self.hits.clear();
+        self.hits.extend(contacts.iter().map(|contact| (self.bodies[contact.source].entity, self.bodies[contact.target].entity)));
+
+        &self.hits
+    }
+
+    /// Pairs of entities that hit each other in the last `run`.
+    pub fn hits(&self) -> &[(Entity, Entity)] {
+        &self.hits
+    }
+
+    /// Applies one point of damage to both sides of every hit of the last
+    /// `run`, and despawns the entities left without hit points.
+    pub fn apply_damage(&mut self, world: &mut World) -> &[Destroyed] {
+        self.destroyed.clear();
+        self.damaged.clear();
+
+        for &(a, b) in self.hits.iter() {
+            for entity in [a, b] {
+                let Some(health) = world.healths.get_mut(entity) else {
+                    continue;
+                };
+
+                health.hit_points = health.hit_points.saturating_sub(1);
+                if health.hit_points > 0 {
+                    if let Some(transform) = world.transforms.get(entity) {
+                        self.damaged.push(Damaged {
+                            team: world.teams.get(entity).copied(),
+                            position: transform.position,
+                            damage: 1,
+                        });
+                    }
+                    continue;
+                }
+
+                if let Some(transform) = world.transforms.get(entity) {
+                    self.destroyed.push(Destroyed {
+                        pool: entity.pool(),
+                        team: world.teams.get(entity).copied(),
+                        position: transform.position,
+                        size: transform.size,
+                    });
+                }
+                world.despawn(entity);
+            }
+        }
+
+        &self.destroyed
+    }
+
+    /// Entities that survived the damage of the last `apply_damage`.
+    pub fn damaged(&self) -> &[Damaged] {
+        &self.damaged
+    }
+}
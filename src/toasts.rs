@@ -0,0 +1,58 @@
+use macroquad::{color::{Color, WHITE}, shapes::draw_rectangle, text::{draw_text, measure_text}, window::screen_width};
+
+/// Seconds a toast stays on screen.
+const TOAST_DURATION: f32 = 4.0;
+const MAX_TOASTS: usize = 4;
+
+struct Toast {
+    text: String,
+    color: Color,
+    remaining: f32,
+}
+
+/// Short messages stacked at the top right of the screen, above every scene.
+#[derive(Default)]
+pub struct Toasts {
+    toasts: Vec<Toast>,
+}
+
+impl Toasts {
+    pub fn push(&mut self, text: impl Into<String>, color: Color) {
+        if self.toasts.len() == MAX_TOASTS {
+            self.toasts.remove(0);
+        }
+
+        self.toasts.push(Toast {
+            text: text.into(),
+            color,
+            remaining: TOAST_DURATION,
+        });
+    }
+
+    pub fn update(&mut self, delta_time: f32) {
+        for toast in self.toasts.iter_mut() {
+            toast.remaining -= delta_time;
+        }
+        self.toasts.retain(|toast| toast.remaining > 0.0);
+    }
+
+    pub fn draw(&self) {
+        const FONT_SIZE: u16 = 22;
+        const PADDING: f32 = 8.0;
+        const TOP: f32 = 50.0;
+
+        for (index, toast) in self.toasts.iter().enumerate() {
+            let text_dimensions = measure_text(&toast.text, None, FONT_SIZE, 1.0);
+            let width = text_dimensions.width + PADDING * 2.0;
+            let height = FONT_SIZE as f32 + PADDING;
+            let x = screen_width() - width - 10.0;
+            let y = TOP + (height + 4.0) * index as f32;
+
+            // Fades out during its last second
+            let alpha = toast.remaining.min(1.0);
+            draw_rectangle(x, y, width, height, Color::new(0.0, 0.0, 0.0, 0.7 * alpha));
+            draw_rectangle(x, y, 4.0, height, Color { a: alpha, ..toast.color });
+            draw_text(&toast.text, x + PADDING, y + FONT_SIZE as f32 - 2.0, FONT_SIZE.into(), Color { a: alpha, ..WHITE });
+        }
+    }
+}
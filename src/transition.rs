@@ -0,0 +1,145 @@
+use macroquad::{
+    color::{Color, BLACK},
+    shapes::draw_rectangle,
+    window::{screen_height, screen_width},
+};
+
+use crate::shaders::DissolveShader;
+
+/// Seconds taken to cover the screen, then again to reveal it.
+const HALF_DURATION: f32 = 0.4;
+const DISSOLVE_BLOCK_SIZE: f32 = 16.0;
+const IRIS_STRIP_HEIGHT: f32 = 4.0;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum TransitionKind {
+    Fade,
+    Wipe,
+    PixelDissolve,
+    Iris,
+    /// Same as `PixelDissolve`, drawn by `DissolveShader`.
+    ShaderDissolve,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum Phase {
+    Covering,
+    Revealing,
+}
+
+/// Covers the screen, lets the caller swap scenes while it is fully covered,
+/// then reveals the new scene.
+pub struct Transition<T> {
+    kind: TransitionKind,
+    phase: Phase,
+    elapsed: f32,
+    /// Handed back once the screen is covered.
+    pending: Option<T>,
+}
+
+impl<T> Transition<T> {
+    pub fn new(kind: TransitionKind, pending: T) -> Self {
+        Transition {
+            kind,
+            phase: Phase::Covering,
+            elapsed: 0.0,
+            pending: Some(pending),
+        }
+    }
+
+    /// Advances the transition by `delta_time` seconds. Returns the pending
+    /// value on the frame the screen becomes fully covered.
+    pub fn update(&mut self, delta_time: f32) -> Option<T> {
+        self.elapsed += delta_time;
+
+        if self.phase == Phase::Covering && self.elapsed >= HALF_DURATION {
+            self.phase = Phase::Revealing;
+            self.elapsed = 0.0;
+            return self.pending.take();
+        }
+
+        None
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.phase == Phase::Revealing && self.elapsed >= HALF_DURATION
+    }
+
+    /// How much of the screen is covered, from 0 to 1.
+    fn coverage(&self) -> f32 {
+        let progress = (self.elapsed / HALF_DURATION).clamp(0.0, 1.0);
+        match self.phase {
+            Phase::Covering => progress,
+            Phase::Revealing => 1.0 - progress,
+        }
+    }
+
+    pub fn draw(&self, dissolve_shader: &mut DissolveShader) {
+        let coverage = self.coverage();
+        let (width, height) = (screen_width(), screen_height());
+
+        match self.kind {
+            TransitionKind::Fade => {
+                draw_rectangle(0.0, 0.0, width, height, Color { a: coverage, ..BLACK });
+            }
+            TransitionKind::Wipe => {
+                // Covers from the left, then uncovers towards the right
+                let covered = width * coverage;
+                let x = if self.phase == Phase::Covering { 0.0 } else { width - covered };
+                draw_rectangle(x, 0.0, covered, height, BLACK);
+            }
+            TransitionKind::PixelDissolve => Self::draw_pixel_dissolve(coverage, width, height),
+            TransitionKind::Iris => Self::draw_iris(coverage, width, height),
+            TransitionKind::ShaderDissolve => dissolve_shader.draw(coverage),
+        }
+    }
+
+    fn draw_pixel_dissolve(coverage: f32, width: f32, height: f32) {
+        let cols = (width / DISSOLVE_BLOCK_SIZE).ceil() as u32;
+        let rows = (height / DISSOLVE_BLOCK_SIZE).ceil() as u32;
+
+        for row in 0..rows {
+            for col in 0..cols {
+                if block_threshold(col, row) < coverage {
+                    draw_rectangle(
+                        col as f32 * DISSOLVE_BLOCK_SIZE,
+                        row as f32 * DISSOLVE_BLOCK_SIZE,
+                        DISSOLVE_BLOCK_SIZE,
+                        DISSOLVE_BLOCK_SIZE,
+                        BLACK,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Black everywhere outside a circle shrinking towards the center.
+    fn draw_iris(coverage: f32, width: f32, height: f32) {
+        let center_x = width / 2.0;
+        let center_y = height / 2.0;
+        let radius = (1.0 - coverage) * center_x.hypot(center_y);
+
+        let mut y = 0.0;
+        while y < height {
+            let distance = (y + IRIS_STRIP_HEIGHT / 2.0 - center_y).abs();
+            let half_chord = if distance < radius { (radius * radius - distance * distance).sqrt() } else { 0.0 };
+
+            let left = (center_x - half_chord).max(0.0);
+            let right = (center_x + half_chord).min(width);
+            draw_rectangle(0.0, y, left, IRIS_STRIP_HEIGHT, BLACK);
+            draw_rectangle(right, y, width - right, IRIS_STRIP_HEIGHT, BLACK);
+
+            y += IRIS_STRIP_HEIGHT;
+        }
+    }
+}
+
+/// Stable pseudo-random value in `[0, 1)` for a dissolve block.
+fn block_threshold(col: u32, row: u32) -> f32 {
+    let mut hash = col.wrapping_mul(0x9E37_79B9) ^ row.wrapping_mul(0x85EB_CA6B);
+    hash ^= hash >> 15;
+    hash = hash.wrapping_mul(0x2C1B_3C6D);
+    hash ^= hash >> 12;
+
+    (hash & 0xFFFF) as f32 / 65536.0
+}
@@ -0,0 +1,286 @@
+use std::fmt::{self, Display};
+
+use crate::{replay::{Checkpoint, Replay}, simulation::{Simulation, SIMULATION_VERSION}, sprites_config::SpriteMasks};
+
+/// Claimed durations may be off by this many seconds, as they go through
+/// text formats.
+const DURATION_TOLERANCE: f32 = 0.01;
+
+/// How a run ended, as claimed by a submission or found by playing it again.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct RunSummary {
+    pub score: u32,
+    pub wave: u32,
+    /// Seconds survived.
+    pub duration: f32,
+}
+
+impl RunSummary {
+    fn matches(&self, other: &RunSummary) -> bool {
+        self.score == other.score && self.wave == other.wave && (self.duration - other.duration).abs() <= DURATION_TOLERANCE
+    }
+}
+
+impl Display for RunSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "score {}, wave {}, {:.2}s", self.score, self.wave, self.duration)
+    }
+}
+
+fn checkpoint_text(checkpoint: Option<Checkpoint>) -> String {
+    match checkpoint {
+        Some(checkpoint) => format!("score {}, lives {}, wave {}", checkpoint.score, checkpoint.lives, checkpoint.wave),
+        None => "nothing".to_string(),
+    }
+}
+
+/// Why a replay doesn't back its claim.
+#[derive(Clone, Debug)]
+pub enum Rejection {
+    /// Replays only play the same with the simulation that recorded them.
+    SimulationVersion { replay: u32 },
+    /// The state after `frame` isn't the one the replay recorded.
+    Diverged {
+        frame: usize,
+        /// Seconds into the run.
+        time: f32,
+        recorded: Option<Checkpoint>,
+        simulated: Checkpoint,
+    },
+    /// The run was over before the last frame.
+    EndedEarly { frame: usize, frames: usize },
+    /// The run is still going after the last frame.
+    NotOver,
+    Mismatch { claimed: RunSummary, simulated: RunSummary },
+}
+
+impl Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Rejection::SimulationVersion { replay } => write!(
+                f,
+                "the replay was recorded with simulation version {} but this is version {}",
+                replay, SIMULATION_VERSION,
+            ),
+            Rejection::Diverged { frame, time, recorded, simulated } => write!(
+                f,
+                "the replay diverged at frame {} ({:.2}s): it has {} but the simulation has {}",
+                frame,
+                time,
+                checkpoint_text(*recorded),
+                checkpoint_text(Some(*simulated)),
+            ),
+            Rejection::EndedEarly { frame, frames } => write!(f, "the run ended at frame {} of {}", frame, frames),
+            Rejection::NotOver => write!(f, "the run isn't over after the last frame"),
+            Rejection::Mismatch { claimed, simulated } => write!(f, "claimed {} but the replay gives {}", claimed, simulated),
+        }
+    }
+}
+
+/// Plays a replay again and checks it ends with the claimed results.
+///
+/// The state recorded at every checkpoint is compared along the way, so a
+/// replay that doesn't match its run is caught where it starts to differ.
+pub fn verify(replay: &Replay, claim: RunSummary, masks: &SpriteMasks) -> Result<RunSummary, Rejection> {
+    if replay.simulation_version != SIMULATION_VERSION {
+        return Err(Rejection::SimulationVersion {
+            replay: replay.simulation_version,
+        });
+    }
+
+    let mut simulation = Simulation::new(replay.seed, replay.area, replay.rules);
+    let mut checkpoints = replay.checkpoints.iter().peekable();
+    let mut recorded = None;
+    let mut simulated = None;
+
+    for (index, frame) in replay.frames.iter().enumerate() {
+        if simulation.is_over() {
+            return Err(Rejection::EndedEarly {
+                frame: index - 1,
+                frames: replay.frames.len(),
+            });
+        }
+
+        let masks = frame.pixel_perfect_collisions.then_some(masks);
+        simulation.step(frame.delta_time, frame.input, frame.area, masks);
+
+        let recorded_change = checkpoints.next_if(|(frame, _)| *frame == index).map(|(_, checkpoint)| *checkpoint);
+        let checkpoint = simulation.checkpoint();
+        let simulated_change = (simulated != Some(checkpoint)).then_some(checkpoint);
+
+        if recorded_change != simulated_change {
+            return Err(Rejection::Diverged {
+                frame: index,
+                time: simulation.stats.time_survived,
+                recorded: recorded_change.or(recorded),
+                simulated: checkpoint,
+            });
+        }
+
+        recorded = recorded_change.or(recorded);
+        simulated = Some(checkpoint);
+    }
+
+    if !simulation.is_over() {
+        return Err(Rejection::NotOver);
+    }
+
+    let result = RunSummary {
+        score: simulation.score,
+        wave: simulation.enemies.wave,
+        duration: simulation.stats.time_survived,
+    };
+    if !claim.matches(&result) {
+        return Err(Rejection::Mismatch { claimed: claim, simulated: result });
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use macroquad::math::{vec2, Vec2};
+
+    use super::*;
+    use crate::{constants::ASSETS_PATH, difficulty::Difficulty, input::PlayerInput, replay::ReplayFrame, run_rules::RunRules};
+
+    const AREA: Vec2 = vec2(800.0, 600.0);
+    const DELTA_TIME: f32 = 1.0 / 60.0;
+    /// Frames the bot plays before it stops, so even easy runs end.
+    const BOT_FRAMES: u32 = 60 * 60;
+    const MAX_FRAMES: u32 = 60 * 900;
+
+    fn masks() -> SpriteMasks {
+        SpriteMasks::load(Path::new(ASSETS_PATH)).expect("the sprites are in the assets")
+    }
+
+    /// Fires every few frames while sweeping from side to side, then sits
+    /// still until it is shot down.
+    fn bot_input(frame: u32) -> PlayerInput {
+        if frame >= BOT_FRAMES {
+            return PlayerInput::default();
+        }
+
+        let input = if frame.is_multiple_of(6) { PlayerInput::FIRE } else { PlayerInput::default() };
+        input.with(if (frame / 40).is_multiple_of(2) { PlayerInput::LEFT } else { PlayerInput::RIGHT })
+    }
+
+    /// Plays a run of `simulation`, which was just started with `seed`, the
+    /// way the game records it.
+    fn record(simulation: &mut Simulation, seed: u64, rules: RunRules, masks: &SpriteMasks) -> (Replay, RunSummary) {
+        let mut replay = Replay::new(seed, AREA, true, rules);
+
+        let mut frame = 0;
+        while !simulation.is_over() && frame < MAX_FRAMES {
+            let input = bot_input(frame);
+            simulation.step(DELTA_TIME, input, AREA, Some(masks));
+            let replay_frame = ReplayFrame {
+                delta_time: DELTA_TIME,
+                input,
+                area: AREA,
+                pixel_perfect_collisions: true,
+            };
+            replay.record(replay_frame, simulation.checkpoint());
+            frame += 1;
+        }
+        assert!(simulation.is_over(), "the run should end within {} frames", MAX_FRAMES);
+
+        let summary = RunSummary {
+            score: simulation.score,
+            wave: simulation.enemies.wave,
+            duration: simulation.stats.time_survived,
+        };
+        (replay, summary)
+    }
+
+    /// Records the second run of a session, the first one leaving the world
+    /// and the other state of the simulation behind.
+    fn record_second_run(first_seed: u64, seed: u64, rules: RunRules, masks: &SpriteMasks) -> (Replay, RunSummary) {
+        let mut simulation = Simulation::new(first_seed, AREA, rules);
+        record(&mut simulation, first_seed, rules, masks);
+
+        simulation.restart(seed, AREA, rules);
+        record(&mut simulation, seed, rules, masks)
+    }
+
+    #[test]
+    fn second_run_of_a_session_verifies_with_enemy_fire() {
+        let masks = masks();
+        let rules = RunRules { difficulty: Difficulty::Insane, ..Default::default() };
+
+        for seed in 1..=4 {
+            let (replay, summary) = record_second_run(seed * 1000, seed, rules, &masks);
+            let replay = Replay::from_text(&replay.to_text()).unwrap();
+            if let Err(rejection) = verify(&replay, summary, &masks) {
+                panic!("seed {}: {}", seed, rejection);
+            }
+        }
+    }
+
+    fn verify_text(replay: &Replay, summary: RunSummary, masks: &SpriteMasks) -> Result<RunSummary, Rejection> {
+        let replay = Replay::from_text(&replay.to_text()).expect("the replay reads back");
+        verify(&replay, summary, masks)
+    }
+
+    #[test]
+    fn runs_of_every_difficulty_verify() {
+        let masks = masks();
+
+        for difficulty in Difficulty::ALL {
+            let rules = RunRules { difficulty, ..Default::default() };
+            let mut simulation = Simulation::new(42, AREA, rules);
+            let (replay, summary) = record(&mut simulation, 42, rules, &masks);
+
+            assert_eq!(verify_text(&replay, summary, &masks).map_err(|rejection| rejection.to_string()), Ok(summary), "{:?}", difficulty);
+        }
+    }
+
+    #[test]
+    fn restarted_runs_of_every_difficulty_verify() {
+        let masks = masks();
+
+        for difficulty in Difficulty::ALL {
+            let rules = RunRules { difficulty, ..Default::default() };
+            let (replay, summary) = record_second_run(7, 8, rules, &masks);
+
+            assert_eq!(verify_text(&replay, summary, &masks).map_err(|rejection| rejection.to_string()), Ok(summary), "{:?}", difficulty);
+        }
+    }
+
+    #[test]
+    fn wrong_claims_are_rejected() {
+        let masks = masks();
+        let rules = RunRules::default();
+        let mut simulation = Simulation::new(3, AREA, rules);
+        let (replay, summary) = record(&mut simulation, 3, rules, &masks);
+
+        let claim = RunSummary { score: summary.score + 1, ..summary };
+        assert!(matches!(verify_text(&replay, claim, &masks), Err(Rejection::Mismatch { .. })));
+    }
+
+    #[test]
+    fn tampered_inputs_diverge() {
+        let masks = masks();
+        let rules = RunRules::default();
+        let mut simulation = Simulation::new(3, AREA, rules);
+        let (mut replay, summary) = record(&mut simulation, 3, rules, &masks);
+
+        for frame in replay.frames.iter_mut() {
+            frame.input = PlayerInput::default();
+        }
+        assert!(matches!(verify_text(&replay, summary, &masks), Err(Rejection::Diverged { .. } | Rejection::EndedEarly { .. } | Rejection::NotOver)));
+    }
+
+    #[test]
+    fn other_simulation_versions_are_rejected() {
+        let masks = masks();
+        let rules = RunRules::default();
+        let mut simulation = Simulation::new(3, AREA, rules);
+        let (mut replay, summary) = record(&mut simulation, 3, rules, &masks);
+
+        replay.simulation_version = SIMULATION_VERSION - 1;
+        assert!(matches!(verify_text(&replay, summary, &masks), Err(Rejection::SimulationVersion { .. })));
+    }
+}
@@ -0,0 +1,373 @@
+use crate::{components::{Collider, Health, Lifetime, Sprite, Team, Transform, Velocity}, constants::{MAX_BULLETS, MAX_ENEMIES}, pool::{Handle, Pool}};
+
+/// Fixed-capacity pools entities are allocated from.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub enum EntityPool {
+    Hero,
+    Bullets,
+    Enemies,
+}
+
+impl EntityPool {
+    pub const ALL: [EntityPool; 3] = [EntityPool::Hero, EntityPool::Bullets, EntityPool::Enemies];
+
+    pub fn capacity(&self) -> usize {
+        match self {
+            EntityPool::Hero => 1,
+            EntityPool::Bullets => MAX_BULLETS,
+            EntityPool::Enemies => MAX_ENEMIES,
+        }
+    }
+}
+
+/// Handle to an entity of the `World`.
+///
+/// It wraps the generational handle of the entity's pool, so handles to
+/// despawned entities never reach the components of a newer one.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+pub struct Entity {
+    pool: EntityPool,
+    handle: Handle,
+    /// Slot of the entity's components in every `Storage`.
+    index: u32,
+}
+
+impl Entity {
+    pub fn index(&self) -> usize {
+        self.index as usize
+    }
+
+    pub fn pool(&self) -> EntityPool {
+        self.pool
+    }
+}
+
+/// Components of one type, indexed by entity.
+pub struct Storage<T> {
+    slots: Vec<Option<(Entity, T)>>,
+}
+
+impl<T> Storage<T> {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Storage {
+            slots: (0..capacity).map(|_| None).collect(),
+        }
+    }
+
+    pub fn insert(&mut self, entity: Entity, component: T) {
+        self.slots[entity.index()] = Some((entity, component));
+    }
+
+    pub fn remove(&mut self, entity: Entity) -> Option<T> {
+        let slot = self.slots.get_mut(entity.index())?;
+        match slot {
+            Some((owner, _)) if *owner == entity => slot.take().map(|(_, component)| component),
+            _ => None,
+        }
+    }
+
+    pub fn get(&self, entity: Entity) -> Option<&T> {
+        match self.slots.get(entity.index())? {
+            Some((owner, component)) if *owner == entity => Some(component),
+            _ => None,
+        }
+    }
+
+    pub fn get_mut(&mut self, entity: Entity) -> Option<&mut T> {
+        match self.slots.get_mut(entity.index())? {
+            Some((owner, component)) if *owner == entity => Some(component),
+            _ => None,
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, &T)> {
+        self.slots.iter().filter_map(|slot| slot.as_ref().map(|(entity, component)| (*entity, component)))
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Entity, &mut T)> {
+        self.slots.iter_mut().filter_map(|slot| slot.as_mut().map(|(entity, component)| (*entity, component)))
+    }
+
+    pub fn clear(&mut self) {
+        for slot in self.slots.iter_mut() {
+            *slot = None;
+        }
+    }
+}
+
+/// Types that can be attached to an entity.
+pub trait Component: Sized {
+    fn storage(world: &World) -> &Storage<Self>;
+    fn storage_mut(world: &mut World) -> &mut Storage<Self>;
+}
+
+macro_rules! impl_component {
+    ($component:ty, $field:ident) => {
+        impl Component for $component {
+            fn storage(world: &World) -> &Storage<Self> {
+                &world.$field
+            }
+
+            fn storage_mut(world: &mut World) -> &mut Storage<Self> {
+                &mut world.$field
+            }
+        }
+    };
+}
+
+impl_component!(Transform, transforms);
+impl_component!(Velocity, velocities);
+impl_component!(Sprite, sprites);
+impl_component!(Collider, colliders);
+impl_component!(Health, healths);
+impl_component!(Team, teams);
+impl_component!(Lifetime, lifetimes);
+
+/// Every game object, stored as a set of components.
+///
+/// Entities come from fixed-capacity pools and every storage is allocated up
+/// front, so spawning and despawning never allocate.
+pub struct World {
+    pools: Vec<Pool<()>>,
+    /// First storage slot of each pool.
+    offsets: Vec<u32>,
+
+    pub transforms: Storage<Transform>,
+    pub velocities: Storage<Velocity>,
+    pub sprites: Storage<Sprite>,
+    pub colliders: Storage<Collider>,
+    pub healths: Storage<Health>,
+    pub teams: Storage<Team>,
+    pub lifetimes: Storage<Lifetime>,
+}
+
+impl Default for World {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl World {
+    pub fn new() -> Self {
+        let mut offsets = Vec::with_capacity(EntityPool::ALL.len());
+        let mut capacity = 0;
+        for pool in EntityPool::ALL {
+            offsets.push(capacity as u32);
+            capacity += pool.capacity();
+        }
+
+        World {
+            pools: EntityPool::ALL.iter().map(|pool| Pool::with_capacity(pool.capacity())).collect(),
+            offsets,
+            transforms: Storage::with_capacity(capacity),
+            velocities: Storage::with_capacity(capacity),
+            sprites: Storage::with_capacity(capacity),
+            colliders: Storage::with_capacity(capacity),
+            healths: Storage::with_capacity(capacity),
+            teams: Storage::with_capacity(capacity),
+            lifetimes: Storage::with_capacity(capacity),
+        }
+    }
+
+    /// Allocates an entity from `pool`, `None` when the pool is full.
+    pub fn spawn(&mut self, pool: EntityPool) -> Option<EntityBuilder<'_>> {
+        let (handle, _) = self.pools[pool as usize].acquire()?;
+        let entity = Entity {
+            pool,
+            handle,
+            index: self.offsets[pool as usize] + handle.index() as u32,
+        };
+
+        Some(EntityBuilder { world: self, entity })
+    }
+
+    pub fn despawn(&mut self, entity: Entity) -> bool {
+        if !self.pools[entity.pool as usize].release(entity.handle) {
+            return false;
+        }
+
+        self.transforms.remove(entity);
+        self.velocities.remove(entity);
+        self.sprites.remove(entity);
+        self.colliders.remove(entity);
+        self.healths.remove(entity);
+        self.teams.remove(entity);
+        self.lifetimes.remove(entity);
+
+        true
+    }
+
+    pub fn is_alive(&self, entity: Entity) -> bool {
+        self.pools[entity.pool as usize].contains(entity.handle)
+    }
+
+    /// Live entities and capacity of `pool`.
+    pub fn occupancy(&self, pool: EntityPool) -> (usize, usize) {
+        let pool = &self.pools[pool as usize];
+        (pool.len(), pool.capacity())
+    }
+
+    pub fn len(&self) -> usize {
+        self.pools.iter().map(|pool| pool.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&mut self) {
+        for pool in self.pools.iter_mut() {
+            pool.clear();
+        }
+
+        self.transforms.clear();
+        self.velocities.clear();
+        self.sprites.clear();
+        self.colliders.clear();
+        self.healths.clear();
+        self.teams.clear();
+        self.lifetimes.clear();
+    }
+
+    pub fn insert<C: Component>(&mut self, entity: Entity, component: C) {
+        if self.is_alive(entity) {
+            C::storage_mut(self).insert(entity, component);
+        }
+    }
+
+    pub fn get<C: Component>(&self, entity: Entity) -> Option<&C> {
+        C::storage(self).get(entity)
+    }
+
+    pub fn get_mut<C: Component>(&mut self, entity: Entity) -> Option<&mut C> {
+        C::storage_mut(self).get_mut(entity)
+    }
+}
+
+/// Attaches the components of a freshly spawned entity.
+pub struct EntityBuilder<'a> {
+    world: &'a mut World,
+    entity: Entity,
+}
+
+impl EntityBuilder<'_> {
+    pub fn with<C: Component>(self, component: C) -> Self {
+        C::storage_mut(self.world).insert(self.entity, component);
+        self
+    }
+
+    pub fn build(self) -> Entity {
+        self.entity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use macroquad::math::vec2;
+
+    use crate::{bullet::Bullet, enemy::Enemy};
+
+    use super::*;
+
+    fn spawn_enemy(world: &mut World, x: f32) -> Entity {
+        world.spawn(EntityPool::Enemies).unwrap().with(Transform::new(vec2(x, 0.0), 16.0)).build()
+    }
+
+    #[test]
+    fn despawned_entities_lose_their_components() {
+        let mut world = World::new();
+        let stale = spawn_enemy(&mut world, 1.0);
+        assert!(world.despawn(stale));
+
+        let entity = spawn_enemy(&mut world, 2.0);
+        assert_eq!(entity.index(), stale.index());
+        assert!(!world.is_alive(stale));
+        assert!(world.transforms.get(stale).is_none());
+        assert_eq!(world.transforms.get(entity).map(|transform| transform.position.x), Some(2.0));
+        assert!(!world.despawn(stale));
+    }
+
+    #[test]
+    fn stale_entities_are_not_given_components() {
+        let mut world = World::new();
+        let stale = spawn_enemy(&mut world, 1.0);
+        world.despawn(stale);
+
+        world.insert(stale, Health { hit_points: 3 });
+        assert!(world.healths.iter().next().is_none());
+    }
+
+    #[test]
+    fn clear_spawns_like_a_new_world() {
+        let mut world = World::new();
+        let spawned: Vec<Entity> = (0..5).map(|x| spawn_enemy(&mut world, x as f32)).collect();
+        world.despawn(spawned[1]);
+        world.despawn(spawned[3]);
+        world.clear();
+        assert!(world.is_empty());
+        assert!(world.transforms.iter().next().is_none());
+
+        let mut fresh = World::new();
+        for x in 0..5 {
+            assert_eq!(spawn_enemy(&mut world, x as f32).index(), spawn_enemy(&mut fresh, x as f32).index());
+        }
+        assert!(!world.is_alive(spawned[0]));
+    }
+
+    #[test]
+    fn pools_have_their_own_storage_slots() {
+        let mut world = World::new();
+        let hero = world.spawn(EntityPool::Hero).unwrap().build();
+        let bullet = world.spawn(EntityPool::Bullets).unwrap().build();
+        let enemy = spawn_enemy(&mut world, 0.0);
+
+        assert_ne!(hero.index(), bullet.index());
+        assert_ne!(bullet.index(), enemy.index());
+        assert_eq!(world.occupancy(EntityPool::Bullets), (1, EntityPool::Bullets.capacity()));
+    }
+
+    /// Capacity of every storage, which only changes when one allocates.
+    fn capacities(world: &World) -> [usize; 7] {
+        [
+            world.transforms.slots.capacity(),
+            world.velocities.slots.capacity(),
+            world.sprites.slots.capacity(),
+            world.colliders.slots.capacity(),
+            world.healths.slots.capacity(),
+            world.teams.slots.capacity(),
+            world.lifetimes.slots.capacity(),
+        ]
+    }
+
+    #[test]
+    fn spawning_through_warm_pools_does_not_allocate() {
+        // Components own no heap memory, so replacing one frees nothing
+        fn owns_no_heap_memory<T: Copy>() {}
+        owns_no_heap_memory::<Sprite>();
+
+        let mut world = World::new();
+        let spawn_and_despawn_all = |world: &mut World| {
+            let mut spawned = Vec::new();
+            while let Some(bullet) = Bullet::spawn(world, vec2(10.0, 10.0), 100.0, 1) {
+                spawned.push(bullet);
+            }
+            for size in [16.0, 32.0, 64.0].iter().cycle() {
+                let Some(enemy) = Enemy::spawn_at(world, vec2(10.0, 10.0), *size, 50.0, 1) else {
+                    break;
+                };
+                spawned.push(enemy);
+            }
+            for entity in spawned {
+                assert!(world.despawn(entity));
+            }
+        };
+
+        spawn_and_despawn_all(&mut world);
+        let warm = capacities(&world);
+        for _ in 0..3 {
+            spawn_and_despawn_all(&mut world);
+            assert_eq!(capacities(&world), warm);
+        }
+        assert!(world.is_empty());
+    }
+}
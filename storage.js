@@ -0,0 +1,40 @@
+// localStorage for the WebAssembly build, used by `src/storage.rs`.
+// Strings cross the boundary as UTF-8 bytes in the wasm memory.
+(function () {
+  const encoder = new TextEncoder();
+  const decoder = new TextDecoder();
+
+  function readString(pointer, length) {
+    return decoder.decode(new Uint8Array(wasm_memory.buffer, pointer, length));
+  }
+
+  miniquad_add_plugin({
+    name: "storage",
+    version: 1,
+    register_plugin: function (importObject) {
+      importObject.env.storage_length = function (key, keyLength) {
+        const value = localStorage.getItem(readString(key, keyLength));
+        return value === null ? -1 : encoder.encode(value).length;
+      };
+
+      importObject.env.storage_read = function (key, keyLength, buffer, bufferLength) {
+        const value = localStorage.getItem(readString(key, keyLength)) || "";
+        const bytes = encoder.encode(value).subarray(0, bufferLength);
+        new Uint8Array(wasm_memory.buffer, buffer, bufferLength).set(bytes);
+      };
+
+      importObject.env.storage_write = function (key, keyLength, value, valueLength) {
+        try {
+          localStorage.setItem(readString(key, keyLength), readString(value, valueLength));
+          return 1;
+        } catch (error) {
+          return 0;
+        }
+      };
+
+      importObject.env.storage_remove = function (key, keyLength) {
+        localStorage.removeItem(readString(key, keyLength));
+      };
+    },
+  });
+})();